@@ -26,6 +26,7 @@ use raysnail::prelude::Vec3;
 use raysnail::prelude::Point3;
 use raysnail::prelude::clamp;
 use raysnail::camera::CameraBuilder;
+use raysnail::light::PhysicalSky;
 
 use raysnail::painter::PainterTarget;
 use raysnail::painter::PainterCommand;
@@ -227,7 +228,7 @@ fn render_time_test(width: usize, height: usize,
     let camera = builder.build();    
 
     let mut world = HittableList::default();
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
 
     let rs = 
         Sphere::new(Vec3::new(50.0, 200.0, 200.0), 
@@ -235,7 +236,6 @@ fn render_time_test(width: usize, height: usize,
             Some(Arc::new(DiffuseLight::new(Color::new(1.0, 0.9, 0.8, 1.0)).multiplier(2.0)))
         );
 
-    lights.add(rs.clone());
     world.add(rs);
 
     /*
@@ -310,26 +310,17 @@ fn render_time_test(width: usize, height: usize,
         )))))
     ));
 
-    fn background(ray: &Ray) -> Color {
-
-        assert!((ray.direction.length_squared() - 1.0).abs() < 0.00001);
-
-        let t = 0.5 * (ray.direction.y + 1.0);
-        Color::new(0.68, 0.80, 0.95, 1.0).gradient(&Color::new(0.2, 0.4, 0.7, 1.0), t)
- 
-        // Color::new(0.9, 0.9, 0.9, 1.0)
-        // Color::new(0.1, 0.12, 0.3)
-    }
+    // the sky only as background, the scene has its own lights
+    let sky = PhysicalSky::new(&Vec3::new(0.5, 1.0, 0.3)).intensity(1.5);
 
     let world = World::new(world, 
         lights, 
-        background,
+        move |ray: &Ray| sky.color(ray),
         &(0.0 .. camera.shutter_speed));
 
     camera
         .take_photo()
         .samples(65)
-        .depth(8)
         .shot_to_target(Some("rtow_13_1.ppm"), 
                         &world, target, controller, &PassivePixelController {});
 }
@@ -350,7 +341,7 @@ fn render_raymarching_test(width: usize, height: usize,
     let camera = builder.build();    
 
     let mut world = HittableList::default();
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
 
     let rs = 
         Sphere::new(Vec3::new(300.0, 400.0, 100.0), 
@@ -358,7 +349,6 @@ fn render_raymarching_test(width: usize, height: usize,
             Some(Arc::new(DiffuseLight::new(Color::new(1.0, 0.9, 0.8, 1.0)).multiplier(1.5)))
         );
 
-    lights.add(rs.clone());
     world.add(rs);
 
     // let color = Color::new(0.8, 0.8, 0.8, 1.0);
@@ -380,27 +370,18 @@ fn render_raymarching_test(width: usize, height: usize,
         )))))
     ));
 
-    fn background(ray: &Ray) -> Color {
-
-        // assert!((ray.direction.length_squared() - 1.0).abs() < 0.00001);
-
-        let t = 0.5 * (ray.direction.y + 1.0);
-        Color::new(0.68, 0.80, 0.95, 1.0).gradient(&Color::new(0.2, 0.4, 0.7, 1.0), t)
- 
-        // Color::new(0.9, 0.9, 0.9)
-        // Color::new(0.06, 0.06, 0.25)
-    }
+    // the sky only as background, the scene has its own lights
+    let sky = PhysicalSky::new(&Vec3::new(0.5, 1.0, 0.3)).intensity(1.5);
 
 
     let world = World::new(world, 
         lights, 
-        background,
+        move |ray: &Ray| sky.color(ray),
         &(0.0 .. camera.shutter_speed));
 
     camera
         .take_photo()
         .samples(122)
-        .depth(8)
         .shot_to_target(Some("raymarching.ppm"),
                         &world, target, controller, &PassivePixelController {});
     }
@@ -416,7 +397,7 @@ fn render_ball_scene(width: usize, height: usize,
     let camera = camera.width(width);
     let camera = camera.height(height);
 
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
 
     let rs = 
         Sphere::new(Vec3::new(200.0, 400.0, 200.0), 
@@ -424,18 +405,15 @@ fn render_ball_scene(width: usize, height: usize,
             Some(Arc::new(DiffuseLight::new(Color::new(1.0, 0.9, 0.8, 1.0)).multiplier(1.5)))
         );
 
-    lights.add(rs.clone());
     world.add(rs);
 
-    pub fn background(ray: &Ray) -> Color {
-        let t = 0.5 * (ray.direction.y + 1.0);
-        Color::new(1.0, 1.0, 1.0, 1.0).gradient(&Color::new(0.5, 0.7, 1.0, 1.0), t)
-    }
+    // the sky only as background, the scene has its own lights
+    let sky = PhysicalSky::new(&Vec3::new(0.5, 1.0, 0.3)).intensity(1.5);
     
 
     let world = World::new(world, 
         lights, 
-        background,
+        move |ray: &Ray| sky.color(ray),
         &(0.0 .. 0.0));
 
     camera
@@ -443,7 +421,6 @@ fn render_ball_scene(width: usize, height: usize,
         .take_photo()
         .samples(122)
         //.samples(257)
-        .depth(8)
         .shot_to_target(Some("rtow_13_1.ppm"),
                         &world, target, controller, &PassivePixelController {});
 }
@@ -464,7 +441,7 @@ fn render_object_test(width: usize, height: usize,
     let camera = builder.build();    
 
     let mut world = HittableList::default();
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
 
     let rs = 
         Sphere::new(Vec3::new(300.0, 400.0, 100.0), 
@@ -472,7 +449,6 @@ fn render_object_test(width: usize, height: usize,
             Some(Arc::new(DiffuseLight::new(Color::new(1.0, 0.9, 0.8, 1.0)).multiplier(1.5)))
         );
 
-    lights.add(rs.clone());
     world.add(rs);
 
     // let color = Color::new(0.8, 0.8, 0.8, 1.0);
@@ -501,25 +477,17 @@ fn render_object_test(width: usize, height: usize,
             Some(Arc::new(Metal::new(Arc::new(Color::new(0.08, 0.1, 0.06, 1.0)))))
     ));
 
-    fn background(ray: &Ray) -> Color {
-        // assert!((ray.direction.length_squared() - 1.0).abs() < 0.00001);
-
-        let t = 0.5 * (ray.direction.y + 1.0);
-        Color::new(0.68, 0.80, 0.95, 1.0).gradient(&Color::new(0.2, 0.4, 0.7, 1.0), t)
-
-        // Color::new(0.9, 0.9, 0.9, 1.0)
-        // Color::new(0.06, 0.06, 0.25, 1.0)
-    }
+    // the sky only as background, the scene has its own lights
+    let sky = PhysicalSky::new(&Vec3::new(0.5, 1.0, 0.3)).intensity(1.5);
 
     let world = World::new(world, 
         lights, 
-        background,
+        move |ray: &Ray| sky.color(ray),
         &(0.0 .. camera.shutter_speed));
 
     camera
         .take_photo()
         .samples(122)
-        .depth(8)
         .shot_to_target(Some("rtow_13_1.ppm"),
                         &world, target, controller, &PassivePixelController {});
 }
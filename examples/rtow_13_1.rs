@@ -19,7 +19,7 @@ fn main() {
     // Or use `None` to use random seed
     let (camera_builder, mut world) = common::ray_tracing_in_one_weekend::final_scene(Some(7));
 
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
 
     let rs = 
         Sphere::new(Vec3::new(300.0, 400.0, 100.0), 
@@ -27,7 +27,6 @@ fn main() {
             Some(Arc::new(DiffuseLight::new(Color::new(1.0, 0.9, 0.7, 1.0)).multiplier(1.5)))
         );
 
-    lights.add(rs.clone());
     world.add(rs);
 
     let camera = camera_builder
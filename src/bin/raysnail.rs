@@ -16,17 +16,19 @@ use image::Rgb;
 use image::RgbImage;
 use image::ImageFormat;
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 use clap::crate_version;
 
 use std::thread;
 
 use std::sync::Arc;
+use std::f64::consts::PI;
 use std::sync::mpsc::sync_channel;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::SyncSender;
 
 use raysnail::prelude::Ray;
+use raysnail::prelude::Vec3;
 use raysnail::prelude::Color;
 use raysnail::prelude::clamp;
 use raysnail::material::DiffuseLight;
@@ -37,12 +39,32 @@ use raysnail::hittable::collection::World;
 
 use raysnail::camera::CameraBuilder;
 
+use raysnail::integrator::Integrator;
+use raysnail::integrator::AmbientOcclusion;
+use raysnail::integrator::Albedo;
+use raysnail::integrator::Bdpt;
+use raysnail::integrator::BvhCost;
+use raysnail::integrator::Depth;
+use raysnail::integrator::DirectLighting;
+use raysnail::integrator::MaterialId;
+use raysnail::integrator::Normals;
+use raysnail::integrator::Uvs;
+use raysnail::integrator::Whitted;
+
+use raysnail::light::Light;
+use raysnail::light::PointLight;
+use raysnail::light::DirectionalLight;
+use raysnail::light::SpotLight;
+use raysnail::light::PhysicalSky;
+
 use raysnail::painter::PainterTarget;
 use raysnail::painter::PainterCommand;
 use raysnail::painter::PainterController;
 use raysnail::painter::PixelController;
 
 use raysnail::sdl_parser::SdlParser;
+use raysnail::sdl_parser::LightData;
+use raysnail::sdl_parser::LightKind;
 
 
 pub fn init_log(level: &'static str) {
@@ -308,7 +330,65 @@ fn boot_sdl(width: usize, height: usize, receiver: Receiver<(usize, Vec<[f32; 4]
 }
 
 
+// PovRay light colors are the brightness of a white diffuse surface which
+// faces the light, this needs an irradiance of PI. Also PovRay lights don't
+// fall off with distance unless a fade power is given.
+fn build_delta_light(light: LightData) -> Arc<dyn Light> {
+    match light.kind {
+        LightKind::Point => {
+            Arc::new(PointLight::new(light.location, light.color)
+                .multiplier(PI)
+                .fade(light.fade_distance, light.fade_power))
+        },
+        LightKind::Parallel { point_at } => {
+            Arc::new(DirectionalLight::new(&(point_at - light.location), light.color)
+                .multiplier(PI))
+        },
+        LightKind::Spot { point_at, radius, falloff, tightness } => {
+            Arc::new(SpotLight::new(light.location, &point_at, light.color)
+                .radius(radius)
+                .falloff(falloff)
+                .tightness(tightness)
+                .multiplier(PI)
+                .fade(light.fade_distance, light.fade_power))
+        },
+        LightKind::Sphere => {
+            panic!("Sphere lights are no delta lights");
+        },
+    }
+}
+
+
+// The path tracer is the default and needs no integrator, it is set up by the photo settings
+fn build_integrator(name: &str, ao_distance: Option<f64>) -> Result<Option<Arc<dyn Integrator>>, String> {
+    match name {
+        "path" => Ok(None),
+        "bdpt" => Ok(Some(Arc::new(Bdpt::new()))),
+        "direct" => Ok(Some(Arc::new(DirectLighting::new()))),
+        "whitted" => Ok(Some(Arc::new(Whitted::new()))),
+        "ao" => {
+            let ao = AmbientOcclusion::new();
+            Ok(Some(Arc::new(match ao_distance {
+                Some(distance) => ao.distance(distance),
+                None => ao,
+            })))
+        },
+        "normals" => Ok(Some(Arc::new(Normals))),
+        "albedo" => Ok(Some(Arc::new(Albedo::new()))),
+        "uv" => Ok(Some(Arc::new(Uvs))),
+        "depth" => Ok(Some(Arc::new(Depth::new()))),
+        "bvh" => Ok(Some(Arc::new(BvhCost::new()))),
+        "material" => Ok(Some(Arc::new(MaterialId))),
+        _ => Err(format!("Unknown integrator '{}'", name)),
+    }
+}
+
+
 fn parse_and_render(width: usize, height: usize, samples: usize, passes: usize,
+                    spectral: bool,
+                    seed: Option<u64>,
+                    photons: Option<usize>,
+                    integrator: Option<Arc<dyn Integrator>>,
                     filename: &str,
                     target: &mut dyn PainterTarget, 
                     controller: &mut dyn PainterController,
@@ -348,40 +428,77 @@ fn parse_and_render(width: usize, height: usize, samples: usize, passes: usize,
 
     let camera = builder.build();    
 
-    let mut lights = HittableList::default();
+    let lights = HittableList::default();
+    let mut delta_lights = Vec::new();
 
     for light in scene_data.lights {
-        let rs = 
-            Sphere::new(light.location, 
-                12.0, 
-                Some(Arc::new(DiffuseLight::new(light.color).multiplier(1.7)))
-            );
-
-        lights.add(rs.clone());
-        scene_data.hittables.add(rs);
+        if light.kind == LightKind::Sphere {
+            let rs = 
+                Sphere::new(light.location, 
+                    12.0, 
+                    Some(Arc::new(DiffuseLight::new(light.color).multiplier(1.7)))
+                );
+
+            scene_data.hittables.add(rs);
+        }
+        else {
+            delta_lights.push(build_delta_light(light));
+        }
     }
 
-    fn background(ray: &Ray) -> Color {
-        let t = (ray.direction.y + 1.0) * 0.5;  // norm to range 0..1
-        Color::new(0.3, 0.4, 0.5, 1.0).gradient(&Color::new(0.7, 0.89, 1.0, 1.0), t)
-    }
+    let background_color = scene_data.background.clone();
+    let default_sky = PhysicalSky::new(&Vec3::new(0.5, 1.0, 0.3)).intensity(1.5);
+    let background = move |ray: &Ray| -> Color {
+        match &background_color {
+            Some(color) => color.clone(),
+            None => default_sky.color(ray),
+        }
+    };
 
     let redo_controller = RedoController {
         redo_map: redo_map.clone(),
         width,
     };
 
-    let world = World::new(scene_data.hittables, 
-                           lights, 
-                           background,
-                           &(0.0 .. camera.shutter_speed));
+    let mut world = World::new(scene_data.hittables, 
+                               lights, 
+                               background,
+                               &(0.0 .. camera.shutter_speed));
+
+    world.delta_lights = delta_lights;
+
+    if let Some(environment) = scene_data.environment {
+        world.set_environment(environment);
+    }
+
+    if let Some(sky) = scene_data.sky {
+        world.set_sky(sky);
+    }
+
+    // the command line overrides the photon count of the scene
+    let photon_count = photons.or(scene_data.photons.as_ref().map(|p| p.count)).unwrap_or(0);
+    let photon_radius = scene_data.photons.as_ref().and_then(|p| p.radius);
 
     while (pass as usize) < passes {
-        let pixels = 
+        let mut settings =
             camera
                 .take_photo()
                 .samples(samples)
-                .depth(8)
+                .spectral(spectral)
+                .photons(photon_count)
+                .photon_radius(photon_radius);
+
+        if let Some(integrator) = &integrator {
+            settings = settings.integrator(integrator.clone());
+        }
+
+        if let Some(seed) = seed {
+            // each pass needs other samples to improve the image
+            settings = settings.seed(seed.wrapping_add(pass as u64));
+        }
+
+        let pixels = 
+            settings
                 .shot_to_target(Some("sample_scene.ppm"), 
                                 &world, target, controller, &redo_controller);
 
@@ -485,6 +602,32 @@ pub fn main() -> Result<(), String> {
                 .long("height")
                 .help("Image wheight"),
         )
+        .arg(
+            Arg::new("spectral")
+                .long("spectral")
+                .action(ArgAction::SetTrue)
+                .help("Trace single wavelengths to render dispersion. Needs more samples"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed for the random numbers, renders with the same seed give the same image"),
+        )
+        .arg(
+            Arg::new("photons")
+                .long("photons")
+                .help("Number of photons for caustics, 0 turns the photon map off"),
+        )
+        .arg(
+            Arg::new("integrator")
+                .long("integrator")
+                .help("Light transport: path (default), bdpt, direct, whitted, or the debug modes ao, normals, albedo, uv, depth, bvh and material"),
+        )
+        .arg(
+            Arg::new("ao-distance")
+                .long("ao-distance")
+                .help("Only objects closer than this occlude in the ao integrator"),
+        )
         .arg(
             Arg::new("out")
                 .short('o')
@@ -532,9 +675,15 @@ pub fn main() -> Result<(), String> {
         output_file = s;
     }
 
+    let spectral = matches.get_flag("spectral");
+    let seed = matches.get_one::<String>("seed").map(|s| s.parse::<u64>().unwrap());
+    let photons = matches.get_one::<String>("photons").map(|s| s.parse::<usize>().unwrap());
+    let ao_distance = matches.get_one::<String>("ao-distance").map(|s| s.parse::<f64>().unwrap());
+    let integrator = build_integrator(matches.get_one::<String>("integrator").map_or("path", |s| s.as_str()), ao_distance)?;
+
     thread::spawn(move || boot_sdl(width, height, receiver, command_sender));
 
-    parse_and_render(width, height, samples, passes, scene, &mut queue, &mut controller, output_file);
+    parse_and_render(width, height, samples, passes, spectral, seed, photons, integrator, scene, &mut queue, &mut controller, output_file);
 
     Ok(())
 }
@@ -1,13 +1,12 @@
 use {
     crate::{
-        hittable::{
-            collection::World,
-            Hittable,
-        },
+        hittable::collection::World,
+        integrator::{Integrator, PathTracer},
         painter::{Painter, PainterTarget, PassivePainterTarget},
+        photon_map::PhotonMap,
         prelude::*,
     },
-    std::path::Path,
+    std::{path::Path, sync::Arc},
 };
 use crate::painter::PainterController;
 use crate::painter::PassivePainterController;
@@ -91,23 +90,20 @@ impl Camera {
 }
 
 
-fn phong_highlight(dir_to_light: &Vec3, ray_dir: &Vec3, normal: &Vec3, exponent: i32, factor: f64) -> f64 {
-    let reflected = dir_to_light - 2.0 * dir_to_light.dot(normal) * normal;
-    let specular = reflected.dot(&-ray_dir.clone()).max(0.0).powi(exponent);
-    // println!("phong = {}", specular * factor);
-
-    specular * factor
-}
-
-
 #[derive(Debug)]
 pub struct TakePhotoSettings<'c> {
     camera: &'c Camera,
     depth: usize,
+    russian_roulette_depth: usize,
+    spectral: bool,
+    seed: Option<u64>,
+    photons: usize,
+    photon_radius: Option<f64>,
     gamma: bool,
     samples: usize,
     threads: usize,
     parallel: bool,
+    integrator: Option<Arc<dyn Integrator>>,
 }
 
 impl<'c> TakePhotoSettings<'c> {
@@ -115,20 +111,72 @@ impl<'c> TakePhotoSettings<'c> {
     pub const fn new(camera: &'c Camera) -> Self {
         Self {
             camera,
-            depth: 8,
+            depth: 64,
+            russian_roulette_depth: 3,
+            spectral: false,
+            seed: None,
+            photons: 0,
+            photon_radius: None,
             gamma: true,
             samples: 50,
             threads: 0,
             parallel: true,
+            integrator: None,
         }
     }
 
+    /// Hard limit for the number of bounces of a path
     #[must_use]
     pub const fn depth(mut self, depth: usize) -> Self {
         self.depth = depth;
         self
     }
 
+    /**
+     * Paths are terminated randomly by russian roulette once they reached
+     * this depth. Lower values are faster, but add noise.
+     */
+    #[must_use]
+    pub const fn russian_roulette(mut self, min_depth: usize) -> Self {
+        self.russian_roulette_depth = min_depth;
+        self
+    }
+
+    /**
+     * In spectral mode each path carries a single wavelength, which makes
+     * wavelength dependent effects like dispersion visible. This needs more
+     * samples than RGB mode to get the same noise level.
+     */
+    #[must_use]
+    pub const fn spectral(mut self, spectral: bool) -> Self {
+        self.spectral = spectral;
+        self
+    }
+
+    /// Renders with the same seed give the same image
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /**
+     * Number of photons to emit for a caustics photon map, which is built
+     * before rendering. Zero renders without photon map.
+     */
+    #[must_use]
+    pub const fn photons(mut self, photons: usize) -> Self {
+        self.photons = photons;
+        self
+    }
+
+    /// Largest radius to gather photons from, by default derived from the scene size
+    #[must_use]
+    pub const fn photon_radius(mut self, radius: Option<f64>) -> Self {
+        self.photon_radius = radius;
+        self
+    }
+
     #[must_use]
     pub const fn gamma(mut self, gamma: bool) -> Self {
         self.gamma = gamma;
@@ -153,108 +201,16 @@ impl<'c> TakePhotoSettings<'c> {
         self
     }
 
-    fn ray_color(ray: &Ray, world: &World, depth: usize, rng: &mut FastRng) -> Vec3 {
-        
-        // info!("ray_color depth={}", depth);
-
-        // If we've exceeded the ray bounce limit, no more light is gathered.
-        if depth == 0 {
-            return Vec3::default();
-        }
-
-        if let Some(hit) = world.hit(ray, &(0.0001..f64::INFINITY)) {
-            let material = 
-                match hit.material {
-                    Some(ref m) => m.clone(),
-                    None => world.default_material.clone(),
-                };
-            
-            let emitted = material
-                .emitted(hit.u, hit.v, &hit.point)
-                .unwrap_or_default();
-
-            if let Some(srec) = material.scatter(ray, &hit) {
-
-                if srec.skip_pdf {
-                    // If the material skips the pdf it must provide a ray in the record
-                    if srec.ray.is_some() {
-                        return emitted + srec.color * Self::ray_color(&srec.ray.unwrap(), world, depth-1, rng);
-                    }
-                    else {
-                        // dead end material, doesn't scatter light
-                        return emitted + srec.color * Vec3::new(1.0, 1.0, 1.0);
-                    }
-                }
-/*
-                let light_pdf = HittablePdf::new(&world.lights, &hit.point, &hit.normal);
-                let mixture = MixturePdf::new(&light_pdf, srec.pdf.as_ref());
-                let scatter_direction = mixture.generate(rng);
-*/
-
-                let mut light_multi = 1.0;
-                let mut pdf_val;
-
-                let scattered_ray = 
-                    if rng.gen() < 0.5 { 
-                        pdf_val = 0.3183098861837907;
-
-                        let dir_to_light = world.lights.random(&hit.point, rng).unit();
-                        let settings = material.settings();
-                        if settings.phong_factor > 0.0 {
-                            light_multi += phong_highlight(&-dir_to_light.clone(), &ray.direction, &hit.normal, 
-                                                           settings.phong_exponent, settings.phong_factor);
-                        }
-
-                        // we must trace back some distance, a beam to the light source can easily
-                        // hit the same body again, but if the ŕay starts to close, the intersection
-                        // will be ignored
-                        let start = ray.at(hit.t1 - 0.0002);
-                        Ray::new(start, dir_to_light, ray.departure_time)
-                    } 
-                    else {
-                        let scatter_direction = srec.pdf.generate(rng);
-                        pdf_val = srec.pdf.value(&scatter_direction);
-                        Ray::new(hit.point.clone(), scatter_direction, ray.departure_time)
-                    };
-
-                // println!("hit normal={:?} scatter dir={:?}", hit.normal, scatter_direction);
-
-/*
-                let light_pdf = HittablePdf::new(&world.lights, &hit.point, &scattered.direction);
-                let mixture = MixturePdf::new(&light_pdf, srec.pdf.as_ref());                
-                let mut pdf_val = mixture.value(&scattered.direction);
-
-                // let light_pdf_val = CosinePdf::new(&scattered.direction).value(&scattered.direction);                
-                let light_pdf_val = 0.3183098861837907;
-
-                let mut pdf_val =
-                    0.5 * srec.pdf.value(&scattered_ray.direction) +
-                    0.5 * light_pdf_val;
-*/
-
-                // clean NaNs and extreme cases
-                if pdf_val <= 0.0 || pdf_val != pdf_val {
-                    pdf_val = 1e-5;
-                }
-
-                // let scattering_pdf_val = material.scattering_pdf(ray, &hit, &scattered_ray);
-                let scattering_pdf_val = srec.pdf.value(&scattered_ray.direction);
-                let pdf_multiplicator = scattering_pdf_val / pdf_val;
-
-                let sample_color = light_multi * Self::ray_color(&scattered_ray, world, depth-1, rng);
-                let color_from_scatter = (srec.color * sample_color) * pdf_multiplicator;
-                
-                return emitted + color_from_scatter;
-            }
-            
-            return emitted;
-        }
-
-        // If the ray hits nothing, return the background color.
-        world.background(ray).into()
+    /**
+     * Renders with the given integrator instead of the default path tracer.
+     * Depth, russian roulette and photons only apply to the path tracer.
+     */
+    #[must_use]
+    pub fn integrator(mut self, integrator: Arc<dyn Integrator>) -> Self {
+        self.integrator = Some(integrator);
+        self
     }
 
-
     /// # Errors
     /// When open or save to file failed
     #[allow(clippy::needless_pass_by_value)] // Directly used public API, add & will make it harder to use
@@ -263,6 +219,16 @@ impl<'c> TakePhotoSettings<'c> {
                                           target: &mut dyn PainterTarget,
                                           controller: &mut dyn PainterController,
                                           pixel_map: &dyn PixelController) -> Vec<[f32; 4]> {
+        let integrator = self.integrator.clone().unwrap_or_else(|| {
+            let mut tracer = PathTracer::new()
+                .depth(self.depth)
+                .russian_roulette(self.russian_roulette_depth);
+            if self.photons > 0 {
+                tracer = tracer.photon_map(PhotonMap::new(world, self.photons, self.photon_radius, self.seed));
+            }
+            Arc::new(tracer)
+        });
+
         // because picture height/width is always positive and small enough in practice
         #[allow(
             clippy::cast_sign_loss,
@@ -275,6 +241,7 @@ impl<'c> TakePhotoSettings<'c> {
             .samples(self.samples)
             .threads(self.threads)
             .parallel(self.parallel)
+            .seed(self.seed)
             .draw(&path, target, controller, pixel_map,
                 |i: f64, j: f64, rng: &mut FastRng| -> Vec3 {
 
@@ -282,7 +249,15 @@ impl<'c> TakePhotoSettings<'c> {
 
                     let ray = self.camera.ray(i, j, rng);
                     // info!("uv_color 2 {}, {}", i, j);
-                    Self::ray_color(&ray, world, self.depth, rng)
+
+                    if self.spectral {
+                        let wavelength = sample_wavelength(rng);
+                        let color = integrator.ray_color(ray.with_wavelength(Some(wavelength)), world, rng);
+                        color * wavelength_weight(wavelength)
+                    }
+                    else {
+                        integrator.ray_color(ray, world, rng)
+                    }
                 })
     }
 
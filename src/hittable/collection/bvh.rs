@@ -14,16 +14,40 @@ use log::info;
 use std::sync::Arc;
 
 
+/**
+ * A child of a node is either another node, which the traversal descends
+ * into directly, or one of the objects of the hierarchy.
+ */
+enum Child {
+    Leaf(Arc<dyn Hittable>),
+    Node(Arc<BVH>),
+}
+
+impl Child {
+    fn hit<C: FnMut()>(&self, ray: &Ray, unit_limit: &Range<f64>, count: &mut C) -> Option<HitRecord> {
+        match self {
+            Self::Leaf(object) => object.hit(ray, unit_limit),
+            Self::Node(node) => node.hit_tree(ray, unit_limit, count),
+        }
+    }
+}
+
+
+/**
+ * Objects without a bounding box, like planes, can't be sorted into the
+ * hierarchy. They are kept in a separate list and tested for every ray.
+ */
 #[derive(Default)]
 pub struct BVH {
     bbox: Option<AABB>,
-    left: Option<Arc<dyn Hittable>>,
-    right: Option<Arc<dyn Hittable>>,
+    left: Option<Child>,
+    right: Option<Child>,
+    unbounded: Vec<Arc<dyn Hittable>>,
 }
 
 impl Debug for BVH {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("BVH {{ bbox: {:?} }}", self.bbox))
+        f.write_fmt(format_args!("BVH {{ bbox: {:?}, unbounded: {} }}", self.bbox, self.unbounded.len()))
     }
 }
 
@@ -45,14 +69,78 @@ fn cmp_geometry_by(axis: usize, a: &dyn Hittable, b: &dyn Hittable) -> Ordering
 impl BVH {
     #[must_use]
     pub fn new(objects: HittableList, time_limit: &Range<f64>) -> Self {
-        let objects = objects.into_objects();
-        if objects.is_empty() {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = objects
+            .into_objects()
+            .into_iter()
+            .partition(|object| object.bbox(time_limit).is_some());
+
+        let mut bvh = if bounded.is_empty() {
             Self::default()
         } else {
-            let mut objects: Vec<_> = objects.into_iter().map(Some).collect();
+            let mut objects: Vec<_> = bounded.into_iter().map(Some).collect();
             let count = objects.len();
             Self::new_internal(&mut objects, 0..count, time_limit)
+        };
+
+        bvh.unbounded = unbounded;
+        bvh
+    }
+
+    /// The bounding box of the objects in the hierarchy, without the unbounded ones
+    #[must_use]
+    pub fn bounded_bbox(&self) -> Option<AABB> {
+        self.bbox.clone()
+    }
+
+    /**
+     * The closest hit like `hit`, together with the number of bounding boxes
+     * tested on the way, for the traversal cost heatmap.
+     */
+    #[must_use]
+    pub fn hit_counted(&self, ray: &Ray, unit_limit: &Range<f64>) -> (Option<HitRecord>, usize) {
+        let mut tests = 0;
+        let (limit, closest) = self.hit_unbounded(ray, unit_limit);
+        let hit = self.hit_tree(ray, &limit, &mut || tests += 1).or(closest);
+
+        (hit, tests)
+    }
+
+    // the limit ends at the closest unbounded hit, so a hit in the tree is closer
+    fn hit_unbounded(&self, ray: &Ray, unit_limit: &Range<f64>) -> (Range<f64>, Option<HitRecord>) {
+        let mut limit = unit_limit.clone();
+        let mut closest = None;
+
+        for object in &self.unbounded {
+            if let Some(hit) = object.hit(ray, &limit) {
+                limit.end = hit.t1;
+                closest = Some(hit);
+            }
+        }
+
+        (limit, closest)
+    }
+
+    // count is called for every bounding box test, it does nothing outside of the heatmap
+    fn hit_tree<C: FnMut()>(&self, ray: &Ray, unit_limit: &Range<f64>, count: &mut C) -> Option<HitRecord> {
+        let bbox = self.bbox.as_ref()?;
+        count();
+        if !bbox.hit(ray, unit_limit) {
+            return None;
         }
+
+        let hit_left = self
+            .left
+            .as_ref()
+            .and_then(|left| left.hit(ray, unit_limit, count));
+        let hit_right = self.right.as_ref().and_then(|right| {
+            let right_limit = unit_limit.start .. hit_left
+                    .as_ref()
+                    .map_or(unit_limit.end, |record| record.t1);
+            right.hit(ray, &right_limit, count)
+        });
+
+        // Right has small t then left if it return `Some`, so right appear first
+        hit_right.or(hit_left)
     }
 
     fn new_internal(
@@ -67,8 +155,9 @@ impl BVH {
                 .expect("No bounding box in bvh_node constructor.");
             Self {
                 bbox: Some(bbox),
-                left: Some(left),
+                left: Some(Child::Leaf(left)),
                 right: None,
+                unbounded: Vec::new(),
             }
         } else if count == 2 {
             let left = objects[index.start].take().unwrap();
@@ -81,8 +170,9 @@ impl BVH {
                 .expect("No bounding box in bvh_node constructor.");
             Self {
                 bbox: Some(left_bbox | right_bbox),
-                left: Some(left),
-                right: Some(right),
+                left: Some(Child::Leaf(left)),
+                right: Some(Child::Leaf(right)),
+                unbounded: Vec::new(),
             }
         } else {
 
@@ -105,8 +195,9 @@ impl BVH {
             let right = Arc::new(Self::new_internal(objects, mid..index.end, time_limit));
             Self {
                 bbox: Some(left.bbox.as_ref().unwrap() | right.bbox.as_ref().unwrap()),
-                left: Some(left),
-                right: Some(right),
+                left: Some(Child::Node(left)),
+                right: Some(Child::Node(right)),
+                unbounded: Vec::new(),
             }
         }
     }
@@ -171,24 +262,8 @@ fn find_best_axis(objects: &mut Vec<Option<Arc<dyn Hittable>>>, time_limit: &Ran
 /// Bounding Volume Hierarchies
 impl Hittable for BVH {
     fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
-        let bbox = self.bbox.as_ref()?;
-        if !bbox.hit(ray, unit_limit) {
-            return None;
-        }
-
-        let hit_left = self
-            .left
-            .as_ref()
-            .and_then(|left| left.hit(ray, unit_limit));
-        let hit_right = self.right.as_ref().and_then(|right| {
-            let right_limit = unit_limit.start .. hit_left
-                    .as_ref()
-                    .map_or(unit_limit.end, |record| record.t1);
-            right.hit(ray, &right_limit)
-        });
-
-        // Right has small t then left if it return `Some`, so right appear first
-        hit_right.or(hit_left)
+        let (limit, closest) = self.hit_unbounded(ray, unit_limit);
+        self.hit_tree(ray, &limit, &mut || {}).or(closest)
     }
 
     fn contains(&self, _point: &Vec3) -> bool
@@ -200,10 +275,20 @@ impl Hittable for BVH {
     }
 
     fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
-        self.bbox.clone()
+        if self.unbounded.is_empty() {
+            self.bbox.clone()
+        }
+        else {
+            None
+        }
     }
 
     fn random(&self, _origin: &Point3, _rng: &mut FastRng) -> Vec3 {
         Vec3::new(1.0, 0.0, 0.0)
     }
+
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f64 {
+        0.0
+    }
 }
+
@@ -41,16 +41,44 @@ impl HittableList {
         self.objects.clear();
     }
 
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
     #[must_use]
     pub fn into_objects(self) -> Vec<Arc<dyn Hittable>> {
         self.objects
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Hittable>> {
+        self.objects.iter()
+    }
+
     pub fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
         let size = self.objects.len();
         return self.objects[rng.irange(0, size)].random(origin, rng);
     }    
 
+    /**
+     * `random` picks one of the objects with equal probability, so the
+     * density of a direction is the average of the object densities.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let weight = 1.0 / self.objects.len() as f64;
+
+        self.objects
+            .iter()
+            .map(|object| weight * object.pdf_value(origin, direction))
+            .sum()
+    }
+
     pub fn hit(&self, r: &Ray, unit_limit: &Range<f64>) -> Vec<HitRecord> {
 
         let mut hits = Vec::new();
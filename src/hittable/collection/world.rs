@@ -15,6 +15,7 @@ use {
 use std::sync::Arc;
 
 use crate::material::Lambertian;
+use crate::light::{EnvironmentLight, Light, PhysicalSky};
 
 #[must_use]
 pub fn default_background(ray: &Ray) -> Color {
@@ -25,6 +26,7 @@ pub fn default_background(ray: &Ray) -> Color {
 pub struct World {
     bvh: BVH,
     pub lights: HittableList,
+    pub delta_lights: Vec<Arc<dyn Light>>,
     bg_func: Box<dyn Fn(&Ray) -> Color + Send + Sync>,
     pub default_material: Arc<Lambertian>,
 }
@@ -36,27 +38,78 @@ impl Debug for World {
 }
 
 impl World {
+    /**
+     * Emissive objects of the list, e.g. the triangles of a glowing mesh,
+     * are added to the lights automatically. The lights list is for
+     * objects which are not in the list, or which are shared with it.
+     */
     #[must_use]
     pub fn new<F>(list: HittableList, 
-                  lights: HittableList, 
+                  mut lights: HittableList, 
                   background: F,
                   time_range: &Range<f64>) -> Self
         where
                 F: Fn(&Ray) -> Color + Send + Sync + 'static,
     {
+        let emissive: Vec<Arc<dyn Hittable>> = list
+            .iter()
+            .filter(|object| object.emissive() && !lights.iter().any(|light| Arc::ptr_eq(light, object)))
+            .cloned()
+            .collect();
+
+        for object in emissive {
+            lights.add_ref(object);
+        }
+
         Self {
             bvh: BVH::new(list, time_range),
             lights,
+            delta_lights: Vec::new(),
             bg_func: Box::new(background),
             default_material: Arc::new(Lambertian::new(Arc::new(Color::new(1.0, 1.0, 1.0, 1.0)))),
         }
     }
 
+    /**
+     * Adds a light source without a surface, e.g. a point light. Lights with
+     * a surface are hittables with an emissive material instead.
+     */
+    pub fn add_light<L: Light + 'static>(&mut self, light: L) -> &mut Self {
+        self.delta_lights.push(Arc::new(light));
+        self
+    }
+
+    /**
+     * Lights the scene with an environment map. It replaces the background
+     * and joins the lights, so it is importance sampled like them.
+     */
+    pub fn set_environment(&mut self, environment: Arc<EnvironmentLight>) -> &mut Self {
+        self.lights.add_ref(environment.clone());
+        self.bg_func = Box::new(move |ray: &Ray| {
+            let radiance = environment.radiance(&ray.direction);
+            Color::new64(radiance.x, radiance.y, radiance.z, 1.0)
+        });
+        self
+    }
+
+    /// Uses the sky as background and adds its sun to the lights
+    pub fn set_sky(&mut self, sky: PhysicalSky) -> &mut Self {
+        self.add_light(sky.sun());
+        self.bg_func = Box::new(move |ray: &Ray| sky.color(ray));
+        self
+    }
+
     #[must_use]
     pub fn background(&self, ray: &Ray) -> Color {
         let f = &self.bg_func;
         f(ray)
     }
+
+    /// The number of bounding boxes tested to find the closest hit of the ray
+    #[must_use]
+    pub fn box_tests(&self, ray: &Ray, unit_limit: &Range<f64>) -> usize {
+        self.bvh.hit_counted(ray, unit_limit).1
+    }
 }
 
 impl Hittable for World {
@@ -69,11 +122,16 @@ impl Hittable for World {
         self.bvh.contains(point)
     }
 
-    fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
-        self.bvh.bbox(time_limit)
+    // the size of the scene, planes would make every scene infinite
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        self.bvh.bounded_bbox()
     }
 
     fn random(&self, _origin: &Point3, _rng: &mut FastRng) -> Vec3 {
         Vec3::new(1.0, 0.0, 0.0)
     }
+
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f64 {
+        0.0
+    }
 }
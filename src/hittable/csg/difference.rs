@@ -123,4 +123,8 @@ impl Hittable for Difference {
 
         self.plus.random(origin, rng)
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.plus.pdf_value(origin, direction)
+    }
 }
\ No newline at end of file
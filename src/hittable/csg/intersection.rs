@@ -102,12 +102,16 @@ impl Hittable for Intersection {
     fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
         // Some(self.o1.bbox(time_limit).unwrap() | self.o2.bbox(time_limit).unwrap())
 
-        let b1 = self.o1.bbox(time_limit).unwrap();
-        let b2 = self.o2.bbox(time_limit).unwrap();
+        // an unbounded object, e.g. a plane, is limited by the other one
+        let (b1, b2) = match (self.o1.bbox(time_limit), self.o2.bbox(time_limit)) {
+            (Some(b1), Some(b2)) => (b1, b2),
+            (Some(bbox), None) | (None, Some(bbox)) => return Some(bbox),
+            (None, None) => return None,
+        };
 
         let min = Point3::new(b1.min.x.max(b2.min.x),
                               b1.min.y.max(b2.min.y),
-                              b1.min.y.max(b2.min.z));
+                              b1.min.z.max(b2.min.z));
 
         let max = Point3::new(b1.max.x.min(b2.max.x),
                               b1.max.y.min(b2.max.y),
@@ -125,5 +129,9 @@ impl Hittable for Intersection {
         self.o1.random(origin, rng)
     }
 
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.o1.pdf_value(origin, direction)
+    }
+
 
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{collection::HittableList, AARect, AARectMetrics, Hittable, HitRecord},
+        hittable::{collection::HittableList, AARect, AARectMetrics, Hittable, HitRecord, SurfaceSample},
         material::Material,
         prelude::*,
     },
@@ -122,6 +122,10 @@ impl Hittable for Box {
         self.material.clone()
     }
 
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
     fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
 
         let mut hits = self.faces.hit(&ray, unit_limit);
@@ -137,11 +141,13 @@ impl Hittable for Box {
 
             if h1.t1 < h2.t1 {
                 return Some(HitRecord::with_normal(h1.point.clone(), h1.normal.clone(), h1.material.clone(), 
-                                                   (h1.u, h1.v), h1.t1, h2.t1));
+                                                   (h1.u, h1.v), h1.t1, h2.t1)
+                                                   .with_tangent(&h1.tangent));
             }
             else {
                 return Some(HitRecord::with_normal(h2.point.clone(), h2.normal.clone(), h2.material.clone(), 
-                                                   (h2.u, h2.v), h2.t1, h1.t1));
+                                                   (h2.u, h2.v), h2.t1, h1.t1)
+                                                   .with_tangent(&h2.tangent));
             }
         }
 
@@ -160,7 +166,20 @@ impl Hittable for Box {
         self.faces.bbox(time_limit)
     }
 
-    fn random(&self, _origin: &Point3, _rng: &mut FastRng) -> Vec3 {
-        Vec3::new(1.0, 0.0, 0.0)
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.faces.random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.faces.pdf_value(origin, direction)
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        // a face is picked uniformly, the density of the point drops accordingly
+        let face = rng.irange(0, 6);
+        self.faces.iter().nth(face)?.sample_surface(rng).map(|mut sample| {
+            sample.area *= 6.0;
+            sample
+        })
     }
 }
@@ -0,0 +1,382 @@
+use {
+    crate::{
+        hittable::{geometry::disc::disc_bbox, HitRecord, Hittable, SurfaceSample},
+        material::Material,
+        prelude::*,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        ops::Range,
+        sync::Arc,
+    },
+};
+
+
+/**
+ * A cone between two points with a radius at each end, like the POV-Ray
+ * cone. Both radii may be the same, or one of them zero for a pointed cone.
+ * The ends are closed by flat caps, unless the cone is open.
+ *
+ * The calculations take place in a base with the axis as z, the base
+ * point at z = 0 and the cap point at z = height.
+ */
+pub struct Cone {
+    base: Point3,
+    base_radius: f64,
+    cap_radius: f64,
+    height: f64,
+    // change of the radius per unit of height
+    slope: f64,
+    open: bool,
+    onb: ONB,
+    material: Option<Arc<dyn Material>>,
+}
+
+impl Debug for Cone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Cone {{ base: {:?}, base_radius: {}, axis: {:?}, height: {}, cap_radius: {}, open: {} }}",
+            self.base, self.base_radius, self.onb.axis[2], self.height, self.cap_radius, self.open,
+        ))
+    }
+}
+
+impl Cone {
+    #[must_use]
+    pub fn new(base: Point3, base_radius: f64, cap: &Point3, cap_radius: f64, material: Option<Arc<dyn Material>>) -> Self {
+        let axis = cap - &base;
+        let height = axis.length();
+
+        Self {
+            base,
+            base_radius,
+            cap_radius,
+            height,
+            slope: (cap_radius - base_radius) / height,
+            open: false,
+            onb: ONB::build_from(&axis),
+            material,
+        }
+    }
+
+    /// Leaves out the caps, so only the side remains
+    #[must_use]
+    pub const fn open(mut self) -> Self {
+        self.open = true;
+        self
+    }
+
+    fn local(&self, point: &Point3) -> Vec3 {
+        self.onb.to_local(&(point - &self.base))
+    }
+
+    const fn radius_at(&self, z: f64) -> f64 {
+        self.slope.mul_add(z, self.base_radius)
+    }
+
+    // the cap a point lies on, given by its height and radius
+    fn cap_at(&self, local: &Vec3) -> Option<(f64, f64)> {
+        let tolerance = self.height * 1e-7;
+        if self.open {
+            None
+        }
+        else if local.z <= tolerance {
+            Some((0.0, self.base_radius))
+        }
+        else if local.z >= self.height - tolerance {
+            Some((self.height, self.cap_radius))
+        }
+        else {
+            None
+        }
+    }
+
+    fn side_area(&self) -> f64 {
+        let slant = self.height.hypot(self.cap_radius - self.base_radius);
+        PI * (self.base_radius + self.cap_radius) * slant
+    }
+
+    fn cap_area(&self) -> f64 {
+        if self.open {
+            0.0
+        }
+        else {
+            PI * self.base_radius.mul_add(self.base_radius, self.cap_radius * self.cap_radius)
+        }
+    }
+
+    // center and radius of a sphere which encloses the cone
+    fn bounding_sphere(&self) -> (Point3, f64) {
+        let center = &self.base + &self.onb.axis[2] * (self.height * 0.5);
+        let radius = (self.height * 0.5).hypot(self.base_radius.max(self.cap_radius));
+        (center, radius)
+    }
+}
+
+impl Hittable for Cone {
+    fn normal(&self, point: &Point3) -> Vec3 {
+        let local = self.local(point);
+
+        if let Some((z, _)) = self.cap_at(&local) {
+            return if z == 0.0 { -&self.onb.axis[2] } else { self.onb.axis[2].clone() };
+        }
+
+        let radial = local.x.hypot(local.y);
+        if radial == 0.0 {
+            // the tip of a pointed cone
+            return self.onb.axis[2].clone() * -self.slope.signum();
+        }
+
+        self.onb.local(&Vec3::new(local.x / radial, local.y / radial, -self.slope)).unit()
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.material.clone()
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
+    // u goes around the axis and v along it, the caps have a planar mapping
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        let local = self.local(point);
+
+        match self.cap_at(&local) {
+            Some((_, radius)) if radius > 0.0 => (0.5 + 0.5 * local.x / radius, 0.5 + 0.5 * local.y / radius),
+            Some(_) => (0.5, 0.5),
+            None => (local.y.atan2(local.x) / 2.0 / PI + 0.5, local.z / self.height),
+        }
+    }
+
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        let local = self.local(point);
+
+        if self.cap_at(&local).is_some() {
+            Some(self.onb.axis[0].clone())
+        }
+        else {
+            Some(self.onb.local(&Vec3::new(-local.y, local.x, 0.0)))
+        }
+    }
+
+    // In the local base the side is x^2 + y^2 = (r0 + k z)^2, with the
+    // ray O + tD this is a quadratic equation in t
+    #[allow(clippy::suboptimal_flops)] // the coefficients as derived
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let origin = self.local(&ray.origin);
+        let direction = self.onb.to_local(&ray.direction);
+
+        let radius = self.radius_at(origin.z);
+        let growth = self.slope * direction.z;
+
+        let a = direction.x * direction.x + direction.y * direction.y - growth * growth;
+        let b = 2.0 * (origin.x * direction.x + origin.y * direction.y - radius * growth);
+        let c = origin.x * origin.x + origin.y * origin.y - radius * radius;
+
+        // the equation also has the mirrored cone beyond the tip
+        let mut ts: Vec<f64> = solve_quadratic(a, b, c)
+            .into_iter()
+            .filter(|t| (0.0 ..= self.height).contains(&(origin.z + t * direction.z)))
+            .collect();
+
+        if !self.open && direction.z != 0.0 {
+            for (z, radius) in [(0.0, self.base_radius), (self.height, self.cap_radius)] {
+                let t = (z - origin.z) / direction.z;
+                let x = origin.x + t * direction.x;
+                let y = origin.y + t * direction.y;
+                if x * x + y * y <= radius * radius {
+                    ts.push(t);
+                }
+            }
+        }
+
+        ts.sort_by(f64::total_cmp);
+
+        // the surface is entered and left alternately, t2 is where the ray
+        // leaves the part of the cone it is in
+        for (i, t) in ts.iter().enumerate() {
+            if unit_limit.contains(t) {
+                let t2 = ts.get(i + 1).copied().unwrap_or(*t);
+                let hit = HitRecord::new(ray, self, *t, t2);
+                if hit.opaque(ray) {
+                    return Some(hit);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        let local = self.local(point);
+        let radius = self.radius_at(local.z);
+        local.z > 0.0 && local.z < self.height && local.x.hypot(local.y) < radius
+    }
+
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        let axis = &self.onb.axis[2];
+        let cap = &self.base + axis * self.height;
+        Some(disc_bbox(&self.base, axis, self.base_radius) | disc_bbox(&cap, axis, self.cap_radius))
+    }
+
+    /**
+     * This is only called if the object is a light source. Directions are
+     * drawn from the cone of the enclosing sphere.
+     */
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        let (center, radius) = self.bounding_sphere();
+        let direction = center - origin;
+        let distance_squared = direction.length_squared();
+
+        if distance_squared <= radius * radius {
+            return Vec3::random_unit(rng);
+        }
+
+        let uvw = ONB::build_from(&direction);
+        uvw.local(&Vec3::random_to_sphere(radius, distance_squared, rng))
+    }
+
+    // the density of random(), which covers the whole cone of the bounding
+    // sphere, also where the direction misses the cone itself
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let (center, radius) = self.bounding_sphere();
+        let to_center = center - origin;
+        let distance_squared = to_center.length_squared();
+        let radius_squared = radius * radius;
+
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let cos_theta = direction.dot(&to_center) / (direction.length() * distance_squared.sqrt());
+        if cos_theta < cos_theta_max {
+            return 0.0;
+        }
+
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let side_area = self.side_area();
+        let area = side_area + self.cap_area();
+        let (sin, cos) = rng.range(0.0, 2.0 * PI).sin_cos();
+
+        let (point, normal) = if rng.gen() * area < side_area {
+            // the density along the axis grows with the radius
+            let radius_squared = self.base_radius * self.base_radius;
+            let (radius, z) = if self.slope == 0.0 {
+                (self.base_radius, rng.gen() * self.height)
+            }
+            else {
+                let cap_squared = self.cap_radius * self.cap_radius;
+                let radius = rng.gen().mul_add(cap_squared - radius_squared, radius_squared).sqrt();
+                (radius, (radius - self.base_radius) / self.slope)
+            };
+
+            (Vec3::new(radius * cos, radius * sin, z), Vec3::new(cos, sin, -self.slope).unit())
+        }
+        else {
+            // pick a cap by its area
+            let base_area = self.base_radius * self.base_radius;
+            let cap_area = self.cap_radius * self.cap_radius;
+            let (z, radius, up) = if rng.gen() * (base_area + cap_area) < base_area {
+                (0.0, self.base_radius, -1.0)
+            }
+            else {
+                (self.height, self.cap_radius, 1.0)
+            };
+
+            let radius = radius * rng.gen().sqrt();
+            (Vec3::new(radius * cos, radius * sin, z), Vec3::new(0.0, 0.0, up))
+        };
+
+        Some(SurfaceSample {
+            point: &self.base + self.onb.local(&point),
+            normal: self.onb.local(&normal),
+            area,
+        })
+    }
+}
+
+
+/**
+ * A cylinder between two points, like the POV-Ray cylinder. It is a cone
+ * with the same radius at both ends.
+ */
+pub struct Cylinder {
+    cone: Cone,
+}
+
+impl Debug for Cylinder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Cylinder {{ base: {:?}, axis: {:?}, height: {}, radius: {}, open: {} }}",
+            self.cone.base, self.cone.onb.axis[2], self.cone.height, self.cone.base_radius, self.cone.open,
+        ))
+    }
+}
+
+impl Cylinder {
+    #[must_use]
+    pub fn new(base: Point3, cap: &Point3, radius: f64, material: Option<Arc<dyn Material>>) -> Self {
+        Self {
+            cone: Cone::new(base, radius, cap, radius, material),
+        }
+    }
+
+    /// Leaves out the caps, so only the side remains
+    #[must_use]
+    pub fn open(self) -> Self {
+        Self {
+            cone: self.cone.open(),
+        }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn normal(&self, point: &Point3) -> Vec3 {
+        self.cone.normal(point)
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.cone.material()
+    }
+
+    fn emissive(&self) -> bool {
+        self.cone.emissive()
+    }
+
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        self.cone.uv(point)
+    }
+
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        self.cone.tangent(point)
+    }
+
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        self.cone.hit(ray, unit_limit)
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        self.cone.contains(point)
+    }
+
+    fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
+        self.cone.bbox(time_limit)
+    }
+
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.cone.random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.cone.pdf_value(origin, direction)
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        self.cone.sample_surface(rng)
+    }
+}
@@ -0,0 +1,176 @@
+use {
+    crate::{
+        hittable::{HitRecord, Hittable, SurfaceSample},
+        material::Material,
+        prelude::*,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        ops::Range,
+        sync::Arc,
+    },
+};
+
+
+/**
+ * A flat disc around a center, facing the normal. With a hole it is a ring,
+ * like the POV-Ray disc with hole radius.
+ */
+pub struct Disc {
+    center: Point3,
+    radius: f64,
+    hole: f64,
+    // the normal is the z axis of the base
+    onb: ONB,
+    material: Option<Arc<dyn Material>>,
+}
+
+impl Debug for Disc {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Disc {{ center: {:?}, normal: {:?}, radius: {}, hole: {} }}",
+            self.center, self.onb.axis[2], self.radius, self.hole,
+        ))
+    }
+}
+
+impl Disc {
+    #[must_use]
+    pub fn new(center: Point3, normal: &Vec3, radius: f64, material: Option<Arc<dyn Material>>) -> Self {
+        Self {
+            center,
+            radius,
+            hole: 0.0,
+            onb: ONB::build_from(normal),
+            material,
+        }
+    }
+
+    /// Radius of a hole in the middle of the disc
+    #[must_use]
+    pub const fn hole(mut self, radius: f64) -> Self {
+        self.hole = radius;
+        self
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.radius.mul_add(self.radius, -(self.hole * self.hole))
+    }
+
+    fn random_point(&self, rng: &mut FastRng) -> Point3 {
+        // uniform by area, the radius grows with the square root
+        let inner = self.hole * self.hole;
+        let radius = rng.gen().mul_add(self.radius.mul_add(self.radius, -inner), inner).sqrt();
+        let (sin, cos) = rng.range(0.0, 2.0 * PI).sin_cos();
+
+        &self.center + self.onb.local(&Vec3::new(radius * cos, radius * sin, 0.0))
+    }
+
+    // distance along the ray to the disc, whatever the opacity of the material
+    fn distance(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<f64> {
+        let normal = &self.onb.axis[2];
+        let denominator = ray.direction.dot(normal);
+        if denominator == 0.0 {
+            return None;
+        }
+
+        let t1 = (&self.center - &ray.origin).dot(normal) / denominator;
+        if !unit_limit.contains(&t1) {
+            return None;
+        }
+
+        let distance_squared = (ray.at(t1) - &self.center).length_squared();
+        if distance_squared > self.radius * self.radius || distance_squared < self.hole * self.hole {
+            return None;
+        }
+
+        Some(t1)
+    }
+}
+
+impl Hittable for Disc {
+    fn normal(&self, _point: &Point3) -> Vec3 {
+        self.onb.axis[2].clone()
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.material.clone()
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
+    // planar mapping, the disc fills the unit square
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        let local = self.onb.to_local(&(point - &self.center));
+        (0.5 + 0.5 * local.x / self.radius, 0.5 + 0.5 * local.y / self.radius)
+    }
+
+    fn tangent(&self, _point: &Point3) -> Option<Vec3> {
+        Some(self.onb.axis[0].clone())
+    }
+
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let t1 = self.distance(ray, unit_limit)?;
+
+        let hit = HitRecord::new(ray, self, t1, f64::MAX);
+        if !hit.opaque(ray) {
+            return None;
+        }
+
+        Some(hit)
+    }
+
+    fn contains(&self, _point: &Vec3) -> bool {
+        false
+    }
+
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        Some(disc_bbox(&self.center, &self.onb.axis[2], self.radius))
+    }
+
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.random_point(rng) - origin
+    }
+
+    /**
+     * Points are sampled uniformly on the area of the disc, the
+     * area density is converted to a solid angle density here. Cutouts of
+     * the material are left to the shadow ray.
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let ray = Ray::new(origin.clone(), direction.clone(), 0.0);
+
+        let Some(t1) = self.distance(&ray, &(0.0001..f64::INFINITY)) else {
+            return 0.0;
+        };
+
+        let length_squared = direction.length_squared();
+        let distance_squared = t1 * t1 * length_squared;
+        let cosine = direction.dot(&self.onb.axis[2]).abs() / length_squared.sqrt();
+
+        distance_squared / (cosine * self.area())
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        Some(SurfaceSample {
+            point: self.random_point(rng),
+            normal: self.onb.axis[2].clone(),
+            area: self.area(),
+        })
+    }
+}
+
+
+/**
+ * The bounding box of a disc. It is slightly thicker than the disc, so
+ * it still has a volume if the disc lies in an axis plane.
+ */
+pub fn disc_bbox(center: &Point3, normal: &Vec3, radius: f64) -> AABB {
+    let normal = normal.unit();
+    let extent = |n: f64| radius.mul_add(n.mul_add(-n, 1.0).max(0.0).sqrt(), 0.0001);
+    let extent = Vec3::new(extent(normal.x), extent(normal.y), extent(normal.z));
+
+    AABB::new(center - &extent, center + &extent)
+}
@@ -4,12 +4,20 @@ pub(crate) mod sphere;
 pub(crate) mod raymarching;
 pub(crate) mod triangle_mesh;
 pub(crate) mod quadric;
+pub(crate) mod torus;
+pub(crate) mod cone;
+pub(crate) mod disc;
+pub(crate) mod plane;
 
 pub use {
     r#box::Box,
     rect::{AARect, AARectMetrics},
     sphere::Sphere,
     raymarching::RayMarcher,
-    triangle_mesh::TriangleMesh,
+    triangle_mesh::{Triangle, TriangleMesh},
     quadric::Quadric,
+    torus::Torus,
+    cone::{Cone, Cylinder},
+    disc::Disc,
+    plane::Plane,
 };
@@ -0,0 +1,116 @@
+use {
+    crate::{
+        hittable::{HitRecord, Hittable},
+        material::Material,
+        prelude::*,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        ops::Range,
+        sync::Arc,
+    },
+};
+
+
+/**
+ * An infinite plane of the points p with p · normal = distance, like the
+ * POV-Ray plane. For CSG the plane is the boundary of a half space, the
+ * inside is the side the normal points away from.
+ *
+ * The plane has no bounding box, so the bounding volume hierarchy tests it
+ * for every ray.
+ */
+pub struct Plane {
+    distance: f64,
+    // the normal is the z axis of the base
+    onb: ONB,
+    material: Option<Arc<dyn Material>>,
+}
+
+impl Debug for Plane {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Plane {{ normal: {:?}, distance: {} }}",
+            self.onb.axis[2], self.distance,
+        ))
+    }
+}
+
+impl Plane {
+    #[must_use]
+    pub fn new(normal: &Vec3, distance: f64, material: Option<Arc<dyn Material>>) -> Self {
+        Self {
+            distance,
+            onb: ONB::build_from(normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn normal(&self, _point: &Point3) -> Vec3 {
+        self.onb.axis[2].clone()
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.material.clone()
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
+    // textures repeat in unit squares
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        let local = self.onb.to_local(point);
+        (local.x.rem_euclid(1.0), local.y.rem_euclid(1.0))
+    }
+
+    fn tangent(&self, _point: &Point3) -> Option<Vec3> {
+        Some(self.onb.axis[0].clone())
+    }
+
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let normal = &self.onb.axis[2];
+        let denominator = ray.direction.dot(normal);
+        if denominator == 0.0 {
+            return None;
+        }
+
+        let t1 = (self.distance - ray.origin.dot(normal)) / denominator;
+        if !unit_limit.contains(&t1) {
+            return None;
+        }
+
+        // a ray entering the half space stays inside, one leaving it is
+        // outside right after the hit
+        let t2 = if denominator > 0.0 { t1 } else { f64::MAX };
+        let hit = HitRecord::new(ray, self, t1, t2);
+        if !hit.opaque(ray) {
+            return None;
+        }
+
+        Some(hit)
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        point.dot(&self.onb.axis[2]) < self.distance
+    }
+
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        None
+    }
+
+    /**
+     * This is only called if the object is a light source. The plane covers
+     * half of all directions, so they are sampled uniformly.
+     */
+    fn random(&self, _origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        Vec3::random_unit(rng)
+    }
+
+    // the density of random(), also for the directions which miss the plane
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt::Formatter;
 use std::fmt::Debug;
 
 use crate::prelude::FastRng;
+use crate::prelude::PI;
 use crate::prelude::Vec3;
 use crate::prelude::AABB;
 use crate::prelude::Ray;
@@ -103,6 +104,10 @@ impl Hittable for Quadric {
         self.material.clone()
     }
 
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
     fn uv(&self, _point: &Point3) -> (f64, f64) {
         let u = 0.0;
         let v = 0.0;
@@ -188,19 +193,22 @@ impl Hittable for Quadric {
                 point.z * (self.qh * point.z + self.qc * point.x + self.qi) + self.qj) <= 0.0;
     }    
 
+    // Quadrics can be unbounded, intersect them with a box to limit them
     fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
-        Some(AABB::new(
-            Vec3::new(-100.0, -100.0, -100.0),
-            Vec3::new(100.0, 100.0, 100.0),
-        ))
+        None
     }
 
     /**
      * This is only called if the object is a light source. It is used to generate
      * an extra ray towards the light source.
      */
-     fn random(&self, origin: &Point3, _rng: &mut FastRng) -> Vec3 {
+     fn random(&self, _origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        // Quadrics can be unbounded, so there is nothing better than to
+        // sample all directions uniformly
+        Vec3::random_unit(rng)
+    }
 
-        - origin
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f64 {
+        1.0 / (4.0 * PI)
     }
 }
\ No newline at end of file
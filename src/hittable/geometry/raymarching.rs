@@ -9,11 +9,15 @@ use crate::prelude::Point3;
 use crate::prelude::AABB;
 use crate::prelude::Ray;
 use crate::prelude::FastRng;
+use crate::prelude::ONB;
 use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
 
 
+// radius of a sphere around the origin which contains the whole fractal
+const BOUNDING_RADIUS: f64 = 1.3;
+
 #[derive(Clone)]
 pub struct RayMarcher {
 
@@ -166,7 +170,7 @@ impl Hittable for RayMarcher {
 
     fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
         let center = Vec3::new(0.0, 0.0, 0.0);
-        let radius = 1.3;
+        let radius = BOUNDING_RADIUS;
         Some(
             AABB::new(
                 &center - Vec3::new(radius, radius, radius),
@@ -180,8 +184,35 @@ impl Hittable for RayMarcher {
      * This is only called if the object is a light source. It is used to generate
      * an extra ray towards the light source.
      */
-    fn random(&self, _origin: &Point3, rng: &mut FastRng) -> Vec3 {
-        Vec3::random_unit(rng)
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        // sample the cone of the bounding sphere
+        let direction = -origin;
+        let distance_squared = direction.length_squared();
+
+        if distance_squared <= BOUNDING_RADIUS * BOUNDING_RADIUS {
+            return Vec3::random_unit(rng);
+        }
+
+        let uvw = ONB::build_from(&direction);
+        uvw.local(&Vec3::random_to_sphere(BOUNDING_RADIUS, distance_squared, rng))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let to_center = -origin;
+        let distance_squared = to_center.length_squared();
+
+        if distance_squared <= BOUNDING_RADIUS * BOUNDING_RADIUS {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - BOUNDING_RADIUS * BOUNDING_RADIUS / distance_squared).sqrt();
+        let cos_theta = to_center.dot(direction) / (distance_squared * direction.length_squared()).sqrt();
+
+        if cos_theta < cos_theta_max {
+            return 0.0;
+        }
+
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
     }
 }
 
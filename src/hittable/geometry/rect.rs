@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{Hittable, HitRecord},
+        hittable::{Hittable, HitRecord, SurfaceSample},
         material::Material,
         prelude::*,
     },
@@ -78,6 +78,28 @@ impl AARect {
             axis: (1, 2, 0),
         }
     }
+
+    // distance along the ray to the rectangle, whatever the opacity of the material
+    fn distance(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<f64> {
+        let t1 = (self.metrics.k - ray.origin[self.axis.2]) / ray.direction[self.axis.2];
+        if !unit_limit.contains(&t1) {
+            return None;
+        }
+
+        let a = t1.mul_add(ray.direction[self.axis.0], ray.origin[self.axis.0]);
+
+        if a < self.metrics.a0 || a > self.metrics.a1 {
+            return None;
+        }
+
+        let b = t1.mul_add(ray.direction[self.axis.1], ray.origin[self.axis.1]);
+
+        if b < self.metrics.b0 || b > self.metrics.b1 {
+            return None;
+        }
+
+        Some(t1)
+    }
 }
 
 impl Hittable for AARect {
@@ -91,6 +113,10 @@ impl Hittable for AARect {
         self.material.clone()
     }
 
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
     fn uv(&self, point: &Point3) -> (f64, f64) {
         (
             (point[self.axis.0] - self.metrics.a0) / self.metrics.a_len,
@@ -98,25 +124,21 @@ impl Hittable for AARect {
         )
     }
 
-    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
-        let t1 = (self.metrics.k - ray.origin[self.axis.2]) / ray.direction[self.axis.2];
-        if !unit_limit.contains(&t1) {
-            return None;
-        }
-
-        let a = t1.mul_add(ray.direction[self.axis.0], ray.origin[self.axis.0]);
-
-        if a < self.metrics.a0 || a > self.metrics.a1 {
-            return None;
-        }
+    fn tangent(&self, _point: &Point3) -> Option<Vec3> {
+        let mut t = Vec3::default();
+        t[self.axis.0] = 1.0;
+        Some(t)
+    }
 
-        let b = t1.mul_add(ray.direction[self.axis.1], ray.origin[self.axis.1]);
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let t1 = self.distance(ray, unit_limit)?;
 
-        if b < self.metrics.b0 || b > self.metrics.b1 {
+        let hit = HitRecord::new(ray, self, t1, f64::MAX);
+        if !hit.opaque(ray) {
             return None;
         }
 
-        Some(HitRecord::new(ray, self, t1, f64::MAX))
+        Some(hit)
     }
 
     fn contains(&self, _point: &Vec3) -> bool
@@ -139,16 +161,49 @@ impl Hittable for AARect {
     }
 
     fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
-        // axis 2 is distance to the origin
-        // axis 0 and 1 are orthoginal 2d vectors to the fixed axis
+        // axis 2 is the fixed axis, axis 0 and 1 span the rectangle
+
+        let mut point = Point3::default();
+        point[self.axis.0] = rng.range(self.metrics.a0, self.metrics.a1);
+        point[self.axis.1] = rng.range(self.metrics.b0, self.metrics.b1);
+        point[self.axis.2] = self.metrics.k;
+
+        point - origin
+    }
+
+    /**
+     * Points are sampled uniformly on the area of the rectangle, the
+     * area density is converted to a solid angle density here. Cutouts of
+     * the material are left to the shadow ray.
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let ray = Ray::new(origin.clone(), direction.clone(), 0.0);
+
+        let Some(t1) = self.distance(&ray, &(0.0001..f64::INFINITY)) else {
+            return 0.0;
+        };
 
-        // only need axis 2 == y axis at the moment
+        let area = self.metrics.a_len * self.metrics.b_len;
+        let length_squared = direction.length_squared();
+        let distance_squared = t1 * t1 * length_squared;
+        let cosine = direction[self.axis.2].abs() / length_squared.sqrt();
+
+        distance_squared / (cosine * area)
+    }
 
-        let mut root = Vec3::new(0.0, self.metrics.k, 0.0);
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let mut point = Point3::default();
+        point[self.axis.0] = rng.range(self.metrics.a0, self.metrics.a1);
+        point[self.axis.1] = rng.range(self.metrics.b0, self.metrics.b1);
+        point[self.axis.2] = self.metrics.k;
 
-        root.x = rng.range(self.metrics.a0, self.metrics.a1);
-        root.z = rng.range(self.metrics.b0, self.metrics.b1);
+        let mut normal = Vec3::default();
+        normal[self.axis.2] = 1.0;
 
-        origin - root
+        Some(SurfaceSample {
+            point,
+            normal,
+            area: self.metrics.a_len * self.metrics.b_len,
+        })
     }
 }
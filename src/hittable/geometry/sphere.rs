@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{HitRecord, Hittable},
+        hittable::{HitRecord, Hittable, SurfaceSample},
         material::Material,
         prelude::*,
     },
@@ -61,6 +61,10 @@ impl Hittable for Sphere {
         self.material.clone()
     }
 
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
     fn uv(&self, point: &Point3) -> (f64, f64) {
         let point = (point - &self.center).unit();
         let phi = (-point.z).atan2(point.x); // [-pi, pi]
@@ -70,6 +74,12 @@ impl Hittable for Sphere {
         (u, v)
     }
 
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        // derivative of the point by phi, zero at the poles
+        let point = point - &self.center;
+        Some(Vec3::new(point.z, 0.0, -point.x))
+    }
+
     // Ray(t) = O + tD
     // Sphere surface = (X - C)^2 = r^2
     // (O + tD - C)^2 = r^2
@@ -97,12 +107,19 @@ impl Hittable for Sphere {
 
         let t1 = (-half_b - sqrt) / a;
         let t2 = (-half_b + sqrt) / a;
+        // cut out parts of the surface let the ray pass to the far side
         if unit_limit.contains(&t1) {
-            return Some(HitRecord::new(ray, self, t1, t2));
+            let hit = HitRecord::new(ray, self, t1, t2);
+            if hit.opaque(ray) {
+                return Some(hit);
+            }
         }
 
         if unit_limit.contains(&t2) {
-            return Some(HitRecord::new(ray, self, t2, t2));
+            let hit = HitRecord::new(ray, self, t2, t2);
+            if hit.opaque(ray) {
+                return Some(hit);
+            }
         }
 
         None
@@ -149,17 +166,50 @@ impl Hittable for Sphere {
     fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
 
         let direction = &self.center - origin;
+        let distance_squared = direction.length_squared();
+
+        if distance_squared <= self.radius_squared {
+            // origin is inside the sphere, every direction leads to the surface
+            return Vec3::random_unit(rng);
+        }
+
         let uvw = ONB::build_from(&direction);
+        uvw.local(&Vec3::random_to_sphere(self.radius, distance_squared, rng))
+    }
 
-        loop {
-            let u = &uvw.axis[0] * rng.gen();
-            let v = &uvw.axis[1] * rng.gen();
+    /**
+     * Directions are sampled uniformly from the cone that the sphere
+     * covers as seen from the origin.
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
 
-            let uv = u + &v;
+        let to_center = &self.center - origin;
+        let distance_squared = to_center.length_squared();
 
-            if uv.length_squared() < 1.0 {
-                return (uv + &self.center) - origin
-            }
+        if distance_squared <= self.radius_squared {
+            return 1.0 / (4.0 * PI);
+        }
+
+        // the cone of directions towards the sphere, cutouts of the
+        // material are left to the shadow ray
+        let cos_theta_max = (1.0 - self.radius_squared / distance_squared).sqrt();
+        let cos_theta = direction.dot(&to_center) / (direction.length() * distance_squared.sqrt());
+        if cos_theta < cos_theta_max {
+            return 0.0;
         }
+
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+
+        1.0 / solid_angle
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let normal = Vec3::random_unit(rng);
+
+        Some(SurfaceSample {
+            point: self.center_at(0.0) + &normal * self.radius,
+            normal,
+            area: 4.0 * PI * self.radius_squared,
+        })
     }
 }
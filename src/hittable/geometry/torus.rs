@@ -0,0 +1,202 @@
+use {
+    crate::{
+        hittable::{HitRecord, Hittable, SurfaceSample},
+        material::Material,
+        prelude::*,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        ops::Range,
+        sync::Arc,
+    },
+};
+
+
+/**
+ * A torus around the y axis through the origin, like in POV-Ray. The
+ * tube of radius `minor` follows a circle of radius `major` in the
+ * xz plane.
+ */
+#[derive(Clone)]
+pub struct Torus {
+    major: f64,
+    minor: f64,
+    material: Option<Arc<dyn Material>>,
+    // radius of a sphere around the origin which encloses the torus
+    outer: f64,
+}
+
+impl Debug for Torus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Torus {{ major: {}, minor: {} }}",
+            self.major, self.minor,
+        ))
+    }
+}
+
+impl Torus {
+    #[must_use]
+    pub fn new(major: f64, minor: f64, material: Option<Arc<dyn Material>>) -> Self {
+        Self {
+            major,
+            minor,
+            material,
+            outer: major + minor,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn normal(&self, point: &Point3) -> Vec3 {
+        // away from the nearest point of the circle in the middle of the tube
+        let radial = point.x.hypot(point.z);
+        if radial == 0.0 {
+            return Vec3::new(0.0, point.y.signum(), 0.0);
+        }
+
+        let scale = self.major / radial;
+        Vec3::new(point.x * (1.0 - scale), point.y, point.z * (1.0 - scale)).unit()
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.material.clone()
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
+    // u goes around the y axis like on a sphere, v around the tube,
+    // starting at the outer equator and going up first
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        let phi = (-point.z).atan2(point.x);
+        let theta = point.y.atan2(point.x.hypot(point.z) - self.major);
+        (phi / 2.0 / PI + 0.5, theta.rem_euclid(2.0 * PI) / 2.0 / PI)
+    }
+
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        Some(Vec3::new(point.z, 0.0, -point.x))
+    }
+
+    // With |D| = 1 and the ray O + tD the surface
+    // (x^2 + y^2 + z^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2)
+    // becomes a quartic polynomial in t
+    #[allow(clippy::suboptimal_flops)] // the coefficients as derived
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let length = ray.direction.length();
+        let direction = &ray.direction / length;
+
+        // start at the enclosing sphere, rays from far away lose
+        // too much precision in the quartic otherwise
+        let half_b = ray.origin.dot(&direction);
+        let c = ray.origin.length_squared() - self.outer * self.outer;
+        let discriminant = half_b * half_b - c;
+        if discriminant <= 0.0 {
+            return None;
+        }
+
+        let start = (-half_b - discriminant.sqrt()).max(0.0);
+        let origin = &ray.origin + &direction * start;
+
+        let r2 = self.major * self.major;
+        let f = origin.dot(&direction);
+        let e = origin.length_squared() + r2 - self.minor * self.minor;
+        let direction_xz = direction.x * direction.x + direction.z * direction.z;
+        let mixed_xz = origin.x * direction.x + origin.z * direction.z;
+        let origin_xz = origin.x * origin.x + origin.z * origin.z;
+
+        let roots = solve_quartic(
+            4.0 * f,
+            4.0 * f * f + 2.0 * e - 4.0 * r2 * direction_xz,
+            4.0 * f * e - 8.0 * r2 * mixed_xz,
+            e * e - 4.0 * r2 * origin_xz,
+        );
+
+        // the surface is entered and left alternately, t2 is where the ray
+        // leaves the part of the torus it is in
+        let ts: Vec<f64> = roots.iter().map(|root| (root + start) / length).collect();
+        for (i, t) in ts.iter().enumerate() {
+            if unit_limit.contains(t) {
+                let t2 = ts.get(i + 1).copied().unwrap_or(*t);
+                let hit = HitRecord::new(ray, self, *t, t2);
+                if hit.opaque(ray) {
+                    return Some(hit);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        let radial = point.x.hypot(point.z) - self.major;
+        radial.hypot(point.y) < self.minor
+    }
+
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        Some(AABB::new(
+            Vec3::new(-self.outer, -self.minor, -self.outer),
+            Vec3::new(self.outer, self.minor, self.outer),
+        ))
+    }
+
+    /**
+     * This is only called if the object is a light source. Directions are
+     * drawn from the cone of the enclosing sphere.
+     */
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        let direction = -origin;
+        let distance_squared = direction.length_squared();
+        let outer_squared = self.outer * self.outer;
+
+        if distance_squared <= outer_squared {
+            return Vec3::random_unit(rng);
+        }
+
+        let uvw = ONB::build_from(&direction);
+        uvw.local(&Vec3::random_to_sphere(self.outer, distance_squared, rng))
+    }
+
+    // the density of random(), which covers the whole cone of the enclosing
+    // sphere, also where the direction passes through the hole of the torus
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let distance_squared = origin.length_squared();
+        let outer_squared = self.outer * self.outer;
+
+        if distance_squared <= outer_squared {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - outer_squared / distance_squared).sqrt();
+        let cos_theta = direction.dot(&-origin) / (direction.length() * distance_squared.sqrt());
+        if cos_theta < cos_theta_max {
+            return 0.0;
+        }
+
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let phi = rng.range(0.0, 2.0 * PI);
+
+        // the outside of the tube has more area than the inside
+        let theta = loop {
+            let theta = rng.range(0.0, 2.0 * PI);
+            if rng.gen() * self.outer <= self.minor.mul_add(theta.cos(), self.major) {
+                break theta;
+            }
+        };
+
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let normal = Vec3::new(cos_theta * cos_phi, sin_theta, cos_theta * sin_phi);
+        let radial = self.minor.mul_add(cos_theta, self.major);
+
+        Some(SurfaceSample {
+            point: Vec3::new(radial * cos_phi, self.minor * sin_theta, radial * sin_phi),
+            normal,
+            area: 4.0 * PI * PI * self.major * self.minor,
+        })
+    }
+}
@@ -8,6 +8,7 @@ use crate::prelude::Point3;
 use crate::material::Material;
 use crate::hittable::Hittable;
 use crate::hittable::HitRecord;
+use crate::hittable::SurfaceSample;
 use crate::prelude::Ray;
 use crate::prelude::AABB;
 use crate::prelude::FastRng;
@@ -19,6 +20,8 @@ pub struct Triangle {
     normal0: Vec3,
     normal1: Vec3,
     normal2: Vec3,
+    // texture coordinates of the three corners
+    uvs: Option<[(f64, f64); 3]>,
     pub a: f64,
     pub b: f64,
     pub c: f64,
@@ -43,11 +46,14 @@ impl Triangle {
         let minimum = Vec3::new_min(&(Vec3::new_min(&p0, &p1)), &p2);
         let maximum = Vec3::new_max(&(Vec3::new_max(&p0, &p1)), &p2);
         let bounding_box = AABB::new(minimum, maximum);
+        // flat shading until a mesh sets the normals of the corners
+        let normal = (&p1 - &p0).cross(&(&p2 - &p0)).unit();
         Self {
             p0: p0.clone(),
-            normal0: Vec3::new(0.0, 0.0, 0.0),
-            normal1: Vec3::new(0.0, 0.0, 0.0),
-            normal2: Vec3::new(0.0, 0.0, 0.0),
+            normal0: normal.clone(),
+            normal1: normal.clone(),
+            normal2: normal,
+            uvs: None,
             a: &p0.x - &p1.x,
             b: &p0.y - &p1.y,
             c: &p0.z - &p1.z,
@@ -59,6 +65,23 @@ impl Triangle {
         }
     }
 
+    #[must_use]
+    pub fn p1(&self) -> Vec3 {
+        Vec3::new(self.p0.x - self.a, self.p0.y - self.b, self.p0.z - self.c)
+    }
+
+    #[must_use]
+    pub fn p2(&self) -> Vec3 {
+        Vec3::new(self.p0.x - self.d, self.p0.y - self.e, self.p0.z - self.f)
+    }
+
+    /**
+     * Normal of the plane of the triangle, scaled by twice its area
+     */
+    fn plane_normal(&self) -> Vec3 {
+        Vec3::new(self.a, self.b, self.c).cross(&Vec3::new(self.d, self.e, self.f))
+    }
+
     pub fn set_normals(
         &mut self,
         normal0: Vec3,
@@ -69,20 +92,48 @@ impl Triangle {
         self.normal1 = normal1;
         self.normal2 = normal2;
     }
-}
 
-impl Hittable for Triangle {
+    pub const fn set_uvs(&mut self, uv0: (f64, f64), uv1: (f64, f64), uv2: (f64, f64)) {
+        self.uvs = Some([uv0, uv1, uv2]);
+    }
 
-    fn material(&self) -> Option<Arc<dyn Material>> {
-        self.material.clone()
+    fn interpolate_uv(&self, beta: f64, gamma: f64) -> (f64, f64) {
+        match &self.uvs {
+            Some([uv0, uv1, uv2]) => {
+                let alpha = 1.0 - beta - gamma;
+                (gamma.mul_add(uv2.0, alpha * uv0.0 + beta * uv1.0),
+                 gamma.mul_add(uv2.1, alpha * uv0.1 + beta * uv1.1))
+            },
+            None => (0.0, 0.0),
+        }
     }
 
-    fn uv(&self, _point: &Point3) -> (f64, f64) {
-        (0.0, 0.0)
+    /**
+     * The direction of growing u on the triangle, without texture
+     * coordinates the first edge serves as tangent.
+     */
+    fn uv_tangent(&self) -> Vec3 {
+        let e1 = Vec3::new(-self.a, -self.b, -self.c);
+
+        if let Some([uv0, uv1, uv2]) = &self.uvs {
+            let e2 = Vec3::new(-self.d, -self.e, -self.f);
+            let (du1, dv1) = (uv1.0 - uv0.0, uv1.1 - uv0.1);
+            let (du2, dv2) = (uv2.0 - uv0.0, uv2.1 - uv0.1);
+            let det = du1.mul_add(dv2, -(du2 * dv1));
+
+            if det.abs() > 1e-12 {
+                return (e1 * dv2 - e2 * dv1) / det;
+            }
+        }
+
+        e1
     }
 
-    // #[inline(always)]
-    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+    /**
+     * Distance along the ray and barycentric coordinates of the point where
+     * it meets the triangle, whatever the opacity of the material.
+     */
+    fn intersect(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<(f64, f64, f64)> {
         let g = ray.direction.x;
         let h = ray.direction.y;
         let i = ray.direction.z;
@@ -111,23 +162,44 @@ impl Hittable for Triangle {
         }
 
         let t = -(self.f * akjb + self.e * jcal + self.d * blkc) / denom;
-        if t >= unit_limit.start && t <= unit_limit.end {
-            let normal =
-            &self.normal0 * (1.0 - beta - gamma) + &self.normal1 * beta + &self.normal2 * gamma;
-
-            let point = ray.at(t);
-
-            Some(HitRecord::with_normal(
-                point.clone(),
-                normal,
-                self.material(),
-                self.uv(&point),
-                t,
-                f64::MAX,
-            ))
-        } else {
-            None
-        }
+        if t >= unit_limit.start && t <= unit_limit.end { Some((t, beta, gamma)) } else { None }
+    }
+}
+
+impl Hittable for Triangle {
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.material.clone()
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.as_ref().is_some_and(|material| material.emissive())
+    }
+
+    fn uv(&self, _point: &Point3) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    // #[inline(always)]
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let (t, beta, gamma) = self.intersect(ray, unit_limit)?;
+
+        let normal =
+        &self.normal0 * (1.0 - beta - gamma) + &self.normal1 * beta + &self.normal2 * gamma;
+
+        let point = ray.at(t);
+
+        let hit = HitRecord::with_normal(
+            point,
+            normal,
+            self.material(),
+            self.interpolate_uv(beta, gamma),
+            t,
+            f64::MAX,
+        )
+        .with_tangent(&self.uv_tangent());
+
+        if hit.opaque(ray) { Some(hit) } else { None }
     }
 
     fn contains(&self, _point: &Vec3) -> bool
@@ -143,9 +215,45 @@ impl Hittable for Triangle {
      * This is only called if the object is a light source. It is used to generate
      * an extra ray towards the light source.
      */
-     fn random(&self, origin: &Point3, _rng: &mut FastRng) -> Vec3 {
-        origin - &self.p0
+     fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        // uniform point on the triangle from the square root warping
+        // of two random numbers into barycentric coordinates
+        let su = rng.gen().sqrt();
+        let beta = rng.gen() * su;
+        let gamma = 1.0 - su;
+
+        let point = &self.p0 * (1.0 - beta - gamma) + self.p1() * beta + self.p2() * gamma;
+
+        point - origin
      }
+
+    // cutouts of the material are left to the shadow ray
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let ray = Ray::new(origin.clone(), direction.clone(), 0.0);
+
+        if let Some((t, _, _)) = self.intersect(&ray, &(0.0001..f64::INFINITY)) {
+            let normal = self.plane_normal();
+            let area = 0.5 * normal.length();
+            let length_squared = direction.length_squared();
+            let distance_squared = t * t * length_squared;
+            let cosine = normal.dot(direction).abs() / (2.0 * area * length_squared.sqrt());
+
+            distance_squared / (cosine * area)
+        }
+        else {
+            0.0
+        }
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let normal = self.plane_normal();
+
+        Some(SurfaceSample {
+            point: self.random(&Point3::default(), rng),
+            area: 0.5 * normal.length(),
+            normal: normal.unit(),
+        })
+    }
 }
 
 
@@ -243,6 +351,11 @@ impl TriangleMesh {
                 let ind1 = mesh.indices[3 * i + 1] as usize;
                 let ind2 = mesh.indices[3 * i + 2] as usize;
 
+                if !mesh.texcoords.is_empty() {
+                    let uv = |ind: usize| (f64::from(mesh.texcoords[2 * ind]), f64::from(mesh.texcoords[2 * ind + 1]));
+                    triangles[i+i_t].set_uvs(uv(ind0), uv(ind1), uv(ind2));
+                }
+
                 if mesh.normals.is_empty(){
                 triangles[i+i_t].set_normals(
                     v_normal[ind0].unit(),
@@ -17,6 +17,20 @@ pub struct HitRecord {
     pub u: f64,
     pub v: f64,
     pub outside: bool,
+
+    // orthonormal to the normal, the tangent follows the u direction where possible
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
+}
+
+/// A point on the surface of an object, drawn to emit light from it
+#[derive(Debug, Clone)]
+pub struct SurfaceSample {
+    pub point: Point3,
+    pub normal: Vec3,
+    // inverse of the density of the point by area, which is the
+    // surface area of the object if points are drawn uniformly
+    pub area: f64,
 }
 
 impl Debug for HitRecord {
@@ -40,6 +54,7 @@ impl HitRecord {
 
         let material = object.material();
         let (u, v) = object.uv(&point);
+        let (tangent, bitangent) = tangent_frame(&normal, object.tangent(&point).as_ref());
         Self {
             point,
             normal,
@@ -49,11 +64,14 @@ impl HitRecord {
             u,
             v,
             outside,
+            tangent,
+            bitangent,
         }
     }
 
     pub fn with_normal(point: Point3, normal: Vec3, material: Option<Arc<dyn Material>>, uv: (f64, f64), t1: f64, t2: f64) -> Self {
 
+        let (tangent, bitangent) = tangent_frame(&normal, None);
         Self {
             point,
             normal,
@@ -63,6 +81,31 @@ impl HitRecord {
             u: uv.0,
             v: uv.1,
             outside: true,
+            tangent,
+            bitangent,
+        }
+    }
+
+    /**
+     * Replaces the tangent frame, the tangent is made orthogonal to the normal.
+     */
+    #[must_use]
+    pub fn with_tangent(mut self, tangent: &Vec3) -> Self {
+        (self.tangent, self.bitangent) = tangent_frame(&self.normal, Some(tangent));
+        self
+    }
+
+    /// False if the material lets the ray pass through at this hit
+    #[must_use]
+    pub fn opaque(&self, ray: &Ray) -> bool {
+        self.material.as_ref().is_none_or(|material| material.opaque(ray, self))
+    }
+
+    /// The tangent frame as base, with the normal as z axis
+    #[must_use]
+    pub fn onb(&self) -> ONB {
+        ONB {
+            axis: [self.tangent.clone(), self.bitangent.clone(), self.normal.clone()],
         }
     }
 
@@ -77,6 +120,13 @@ impl HitRecord {
     }
 }
 
+fn tangent_frame(normal: &Vec3, tangent: Option<&Vec3>) -> (Vec3, Vec3) {
+    let onb = tangent.map_or_else(|| ONB::build_from(normal), |tangent| ONB::build_from_tangent(normal, tangent));
+    let [tangent, bitangent, _] = onb.axis;
+
+    (tangent, bitangent)
+}
+
 #[allow(unused_variables)]
 pub trait Hittable: Send + Sync {
     fn normal(&self, _point: &Point3) -> Vec3 {
@@ -98,8 +148,24 @@ pub trait Hittable: Send + Sync {
         )
     }
 
+    /**
+     * The direction of growing u at the point, if the object has one.
+     * Without a tangent, hit records get an arbitrary tangent frame.
+     */
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        None
+    }
+
     fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord>;
 
+    /**
+     * Whether the object glows. `World` samples emissive objects as
+     * lights, so they must implement `random()` and `pdf_value()`.
+     */
+    fn emissive(&self) -> bool {
+        false
+    }
+
     fn contains(&self, point: &Vec3) -> bool;
 
     fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB>;
@@ -109,4 +175,67 @@ pub trait Hittable: Send + Sync {
      * an extra ray towards the light source.
      */
      fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3;
+
+    /**
+     * The probability density (by solid angle) that `random` generates the
+     * given direction when called with the same origin. Only called if the
+     * object is a light source.
+     */
+     fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64;
+
+    /**
+     * A random point on the surface, used to send photons from lights into
+     * the scene. Objects which return None don't emit photons.
+     */
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        None
+    }
+}
+
+
+// shared objects, e.g. from the SDL parser, can be used wherever a hittable is expected
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn normal(&self, point: &Point3) -> Vec3 {
+        self.as_ref().normal(point)
+    }
+
+    fn material(&self) -> Option<Arc<dyn Material>> {
+        self.as_ref().material()
+    }
+
+    fn uv(&self, point: &Point3) -> (f64, f64) {
+        self.as_ref().uv(point)
+    }
+
+    fn tangent(&self, point: &Point3) -> Option<Vec3> {
+        self.as_ref().tangent(point)
+    }
+
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        self.as_ref().hit(ray, unit_limit)
+    }
+
+    fn emissive(&self) -> bool {
+        self.as_ref().emissive()
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        self.as_ref().contains(point)
+    }
+
+    fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
+        self.as_ref().bbox(time_limit)
+    }
+
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.as_ref().random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.as_ref().pdf_value(origin, direction)
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        self.as_ref().sample_surface(rng)
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     hittable::{HitRecord, Hittable},
-    material::Isotropic,
+    material::{Isotropic, PhaseFunction},
     prelude::*,
 };
 
@@ -13,6 +13,7 @@ use crate::material::Material;
 pub struct ConstantMedium<T> {
     boundary: T,
     material: Arc<dyn Material>,
+    color: Color,
     density: f64,
     neg_inv_density: f64,
 }
@@ -31,11 +32,19 @@ impl<T> ConstantMedium<T> {
     pub fn new(boundary: T, color: Color, density: f64) -> Self {
         Self {
             boundary,
-            material: Arc::new(Isotropic::new(color)),
+            material: Arc::new(Isotropic::new(color.clone())),
+            color,
             density,
             neg_inv_density: -1.0 / density,
         }
     }
+
+    /// The phase function of the medium, isotropic by default
+    #[must_use]
+    pub fn phase(mut self, phase: PhaseFunction) -> Self {
+        self.material = Arc::new(Isotropic::new(self.color.clone()).phase(phase));
+        self
+    }
 }
 
 impl<T: Hittable> Hittable for ConstantMedium<T> {
@@ -59,7 +68,8 @@ impl<T: Hittable> Hittable for ConstantMedium<T> {
 
         let length_per_unit = ray.direction.length();
         let distance_inside = (rec2.t1 - rec1.t1) * length_per_unit;
-        let hit_distance = self.neg_inv_density * Random::normal().ln();
+        let mut rng = FastRng::for_ray(ray);
+        let hit_distance = self.neg_inv_density * (1.0 - rng.gen()).ln();
 
         if hit_distance > distance_inside {
             return None;
@@ -76,6 +86,8 @@ impl<T: Hittable> Hittable for ConstantMedium<T> {
             u: 0.0,         // useless
             v: 0.0,         // useless
             outside: false, // useless
+            tangent: Vec3::new(0.0, 1.0, 0.0),   // useless
+            bitangent: Vec3::new(0.0, 0.0, 1.0), // useless
         })
     }
 
@@ -91,7 +103,11 @@ impl<T: Hittable> Hittable for ConstantMedium<T> {
         self.boundary.bbox(time_limit)
     }
 
-    fn random(&self, _origin: &Point3, _rng: &mut FastRng) -> Vec3 {
-        Vec3::new(1.0, 0.0, 0.0)
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.boundary.random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.boundary.pdf_value(origin, direction)
     }
 }
@@ -0,0 +1,128 @@
+use crate::{
+    hittable::{HitRecord, Hittable},
+    material::{Isotropic, PhaseFunction},
+    prelude::*,
+    texture::Texture,
+};
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use crate::material::Material;
+
+/**
+ * A participating medium with a density which varies in space, like smoke,
+ * clouds or fog banks. The density is the red channel of a 3D texture,
+ * e.g. `Perlin`, in range 0 .. 1 times the maximum density.
+ *
+ * Scattering points are found by delta tracking: tentative collisions are
+ * sampled as if the medium had the maximum density everywhere, and each is
+ * accepted with the ratio of the local density to the maximum density.
+ */
+pub struct HeterogeneousMedium<T> {
+    boundary: T,
+    material: Arc<dyn Material>,
+    color: Color,
+    density: Arc<dyn Texture>,
+    max_density: f64,
+}
+
+impl<T> Debug for HeterogeneousMedium<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "HeterogeneousMedium {{ max_density: {} }}", self.max_density
+        ))
+    }
+}
+
+
+impl<T> HeterogeneousMedium<T> {
+    #[must_use]
+    pub fn new(boundary: T, color: Color, max_density: f64, density: Arc<dyn Texture>) -> Self {
+        Self {
+            boundary,
+            material: Arc::new(Isotropic::new(color.clone())),
+            color,
+            density,
+            max_density,
+        }
+    }
+
+    /// The phase function of the medium, isotropic by default
+    #[must_use]
+    pub fn phase(mut self, phase: PhaseFunction) -> Self {
+        self.material = Arc::new(Isotropic::new(self.color.clone()).phase(phase));
+        self
+    }
+
+    fn density_at(&self, point: &Point3) -> f64 {
+        let d = f64::from(self.density.color(0.0, 0.0, point).r);
+        clamp(d, 0.0 ..= 1.0) * self.max_density
+    }
+}
+
+impl<T: Hittable> Hittable for HeterogeneousMedium<T> {
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        let mut rec1 = self.boundary.hit(ray, &(f64::NEG_INFINITY..f64::INFINITY))?;
+        let mut rec2 = self.boundary.hit(ray, &(rec1.t1 + 0.0001..f64::INFINITY))?;
+        if rec1.t1 < unit_limit.start {
+            rec1.t1 = unit_limit.start;
+        }
+        if rec2.t1 > unit_limit.end {
+            rec2.t1 = unit_limit.end;
+        }
+        if rec1.t1 >= rec2.t1 || self.max_density <= 0.0 {
+            return None;
+        }
+        if rec1.t1 < 0.0 {
+            rec1.t1 = 0.0;
+        }
+
+        let length_per_unit = ray.direction.length();
+        let mut rng = FastRng::for_ray(ray);
+        let mut t = rec1.t1;
+
+        loop {
+            t -= (1.0 - rng.gen()).ln() / (self.max_density * length_per_unit);
+
+            if t >= rec2.t1 {
+                return None;
+            }
+
+            let point = ray.at(t);
+
+            // real collision, otherwise a null collision and tracking goes on
+            if rng.gen() * self.max_density < self.density_at(&point) {
+                return Some(HitRecord {
+                    point,
+                    normal: Vec3::new(1.0, 0.0, 0.0), // useless,
+                    material: Some(self.material.clone()),
+                    t1: t,
+                    t2: t,
+                    u: 0.0,         // useless
+                    v: 0.0,         // useless
+                    outside: false, // useless
+                    tangent: Vec3::new(0.0, 1.0, 0.0),   // useless
+                    bitangent: Vec3::new(0.0, 0.0, 1.0), // useless
+                });
+            }
+        }
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        self.boundary.contains(point)
+    }
+
+    fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
+        self.boundary.bbox(time_limit)
+    }
+
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.boundary.random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.boundary.pdf_value(origin, direction)
+    }
+}
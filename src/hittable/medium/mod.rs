@@ -1,3 +1,7 @@
 pub(crate) mod constant;
+pub(crate) mod heterogeneous;
 
-pub use constant::ConstantMedium;
+pub use {
+    constant::ConstantMedium,
+    heterogeneous::HeterogeneousMedium,
+};
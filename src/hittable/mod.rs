@@ -9,5 +9,5 @@ pub mod csg;
 pub use {
     geometry::{AARect, AARectMetrics, Box, Sphere},
     csg::Intersection,
-    hit::{HitRecord, Hittable},
+    hit::{HitRecord, Hittable, SurfaceSample},
 };
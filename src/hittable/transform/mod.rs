@@ -1,7 +1,9 @@
 mod transform;
 mod tf_facade;
+mod normal_facade;
 
 pub use {
     transform::{Transform, TransformStack},
     tf_facade::TfFacade,
+    normal_facade::NormalFacade,
 };
@@ -0,0 +1,82 @@
+use crate::{
+    hittable::{HitRecord, Hittable, SurfaceSample},
+    prelude::*,
+    texture::NormalModifier,
+};
+
+use std::sync::Arc;
+use std::ops::Range;
+use std::fmt::Formatter;
+use std::fmt::Debug;
+
+/**
+ * Perturbs the shading normals of the wrapped object with a bump map or a
+ * normal map. Only the normal used for shading changes, the geometry and
+ * thus the hit points stay the same.
+ */
+pub struct NormalFacade {
+    object: Arc<dyn Hittable>,
+    modifier: Arc<dyn NormalModifier>,
+}
+
+impl Debug for NormalFacade {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "NormalFacade {{ modifier: {:?} }}",
+            self.modifier,
+        ))
+    }
+}
+
+impl NormalFacade {
+    pub fn new(object: Arc<dyn Hittable>, modifier: Arc<dyn NormalModifier>) -> Self {
+        Self {
+            object,
+            modifier,
+        }
+    }
+}
+
+impl Hittable for NormalFacade {
+
+    fn hit(&self, ray: &Ray, unit_limit: &Range<f64>) -> Option<HitRecord> {
+        self.object
+            .hit(ray, unit_limit)
+            .map(|mut hit| {
+                let normal = self.modifier.perturb(&hit);
+
+                // normals which tilt away from the surface make no sense
+                if normal.dot(&hit.normal) > 0.0 {
+                    let tangent = hit.tangent.clone();
+                    hit.normal = normal;
+                    hit = hit.with_tangent(&tangent);
+                }
+
+                hit
+            })
+    }
+
+    fn emissive(&self) -> bool {
+        self.object.emissive()
+    }
+
+    fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
+        self.object.bbox(time_limit)
+    }
+
+    fn contains(&self, point: &Vec3) -> bool {
+        self.object.contains(point)
+    }
+
+    fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.object.random(origin, rng)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        self.object.pdf_value(origin, direction)
+    }
+
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        self.object.sample_surface(rng)
+    }
+}
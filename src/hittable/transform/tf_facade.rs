@@ -1,5 +1,5 @@
 use crate::{
-    hittable::{HitRecord, Hittable},
+    hittable::{HitRecord, Hittable, SurfaceSample},
     prelude::*,
 };
 
@@ -49,11 +49,25 @@ impl Hittable for TfFacade {
             .map(|mut hit| {
                 hit.point = self.stack.forward(&hit.point, 1.0);
 
-                hit
+                // the normal follows from the transformed tangent frame, which also
+                // keeps it perpendicular to the surface under non-uniform scaling
+                let tangent = self.stack.forward(&hit.tangent, 0.0);
+                let bitangent = self.stack.forward(&hit.bitangent, 0.0);
+                let mut normal = tangent.cross(&bitangent).unit();
+                if self.stack.determinant() < 0.0 {
+                    normal.reverse();
+                }
+
+                hit.normal = normal;
+                hit.with_tangent(&tangent)
             })
     }
 
 
+    fn emissive(&self) -> bool {
+        self.object.emissive()
+    }
+
     fn bbox(&self, time_limit: &Range<f64>) -> Option<AABB> {
         self.bbox_cache
             .get_or_init(|| {
@@ -95,6 +109,47 @@ impl Hittable for TfFacade {
 
     fn random(&self, origin: &Point3, rng: &mut FastRng) -> Vec3 {
         let r = self.object.random(&self.stack.inverse(origin, 1.0), rng);
-        r
+        self.stack.forward(&r, 0.0)
+    }
+
+    /**
+     * The transform maps directions non-uniformly if it contains a non-uniform
+     * scale, the density of the object must be corrected by the Jacobian of the
+     * direction mapping, which is |det A| / |A w|^3 for a unit direction w.
+     */
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        let local_direction = self.stack.inverse(direction, 0.0);
+        let local_length = local_direction.length();
+
+        let pdf = self.object.pdf_value(&self.stack.inverse(origin, 1.0), &(local_direction / local_length));
+        if pdf == 0.0 {
+            return 0.0;
+        }
+
+        let stretch = direction.length() / local_length;
+
+        pdf * stretch * stretch * stretch / self.stack.determinant().abs()
+    }
+
+    /**
+     * The area of a surface element changes like the parallelogram
+     * spanned by two tangents of the surface.
+     */
+    fn sample_surface(&self, rng: &mut FastRng) -> Option<SurfaceSample> {
+        let sample = self.object.sample_surface(rng)?;
+        let [tangent, bitangent, _] = ONB::build_from(&sample.normal).axis;
+
+        let cross = self.stack.forward(&tangent, 0.0).cross(&self.stack.forward(&bitangent, 0.0));
+        let stretch = cross.length();
+        let mut normal = cross / stretch;
+        if self.stack.determinant() < 0.0 {
+            normal.reverse();
+        }
+
+        Some(SurfaceSample {
+            point: self.stack.forward(&sample.point, 1.0),
+            normal,
+            area: sample.area * stretch,
+        })
     }
 }
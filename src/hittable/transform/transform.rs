@@ -143,6 +143,18 @@ impl TransformStack {
         result
     }
 
+    /**
+     * Determinant of the linear part of the forward transformation
+     */
+    #[must_use]
+    pub fn determinant(&self) -> f64 {
+        let x = self.forward(&Vec3::new(1.0, 0.0, 0.0), 0.0);
+        let y = self.forward(&Vec3::new(0.0, 1.0, 0.0), 0.0);
+        let z = self.forward(&Vec3::new(0.0, 0.0, 1.0), 0.0);
+
+        x.dot(&y.cross(&z))
+    }
+
     pub fn inverse(&self, pos: &Vec3, w: f64) -> Vec3 {
         let mut result = pos.clone();
 
@@ -176,7 +188,7 @@ mod tests {
         assert_eq!(r.z, 18.0);
 
 
-        let r2 = tfs.inv_tf_pos(&r);
+        let r2 = tfs.inverse(&r, 1.0);
 
         assert_eq!(r2.x, 0.0);
         assert_eq!(r2.y, 0.0);
@@ -192,13 +204,13 @@ mod tests {
 
         tfs.push(tf);
 
-        let r = tfs.tf_pos(&Vec3::new(0.0, 0.0, 1.0));
+        let r = tfs.forward(&Vec3::new(0.0, 0.0, 1.0), 1.0);
 
         assert!((r.x - 1.0).abs() < 1e-10);
         assert!((r.y - 0.0).abs() < 1e-10);
         assert!((r.z - 0.0).abs() < 1e-10);
 
-        let r2 = tfs.inv_tf_pos(&r);
+        let r2 = tfs.inverse(&r, 1.0);
 
         assert!((r2.x - 0.0).abs() < 1e-10);
         assert!((r2.y - 0.0).abs() < 1e-10);
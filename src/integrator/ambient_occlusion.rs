@@ -0,0 +1,75 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::Integrator,
+    prelude::*,
+};
+
+
+/**
+ * Gray shading by the fraction of the hemisphere above each visible point
+ * which is open to the surroundings. Creases and contact points get dark,
+ * all materials and lights are ignored. Rays which miss the scene are white.
+ *
+ * With a distance only objects closer than it occlude, so the walls of a
+ * closed room don't make everything black.
+ */
+#[derive(Debug, Clone)]
+pub struct AmbientOcclusion {
+    samples: usize,
+    distance: f64,
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AmbientOcclusion {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            samples: 4,
+            distance: f64::INFINITY,
+        }
+    }
+
+    /// Number of occlusion rays per camera ray
+    #[must_use]
+    pub const fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Objects further away than this don't occlude
+    #[must_use]
+    pub const fn distance(mut self, distance: f64) -> Self {
+        self.distance = distance;
+        self
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    #[allow(clippy::cast_precision_loss)]
+    fn ray_color(&self, ray: Ray, world: &World, rng: &mut FastRng) -> Vec3 {
+        let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+            return Vec3::new(1.0, 1.0, 1.0);
+        };
+
+        let samples = self.samples.max(1);
+        let onb = ONB::build_from(&hit.normal);
+        let start = ray.at(hit.t1 - 0.0002);
+
+        // cosine weighted directions, so open space near the horizon counts less
+        let open = (0 .. samples)
+            .filter(|_| {
+                let direction = onb.local(&Vec3::random_cosine_direction(rng));
+                let occlusion_ray = Ray::new(start.clone(), direction, ray.departure_time);
+                world.hit(&occlusion_ray, &(0.001 .. self.distance)).is_none()
+            })
+            .count();
+
+        let value = open as f64 / samples as f64;
+        Vec3::new(value, value, value)
+    }
+}
@@ -0,0 +1,570 @@
+use {
+    crate::{
+        hittable::{collection::World, HitRecord, Hittable},
+        integrator::{delta_light_color, material_at, sample_material, Integrator},
+        material::Material,
+        prelude::*,
+    },
+    std::sync::Arc,
+};
+
+
+// a scattering point of a camera or light subpath
+struct Vertex {
+    point: Point3,
+    normal: Vec3,
+    // none for the start of a light subpath on the surface of the light
+    hit: Option<HitRecord>,
+    material: Option<Arc<dyn Material>>,
+    // unit direction of travel of the ray which arrived at the vertex
+    incoming: Vec3,
+    // light (or importance) carried to the vertex, divided by the density of the subpath
+    beta: Vec3,
+    // density by area of the vertex when sampled from its predecessor in the subpath
+    pdf_fwd: f64,
+    // density by area of the vertex when sampled from its successor, i.e. by the other subpath
+    pdf_rev: f64,
+    // left along a specular lobe, whose density isn't defined
+    delta: bool,
+    // has a scattering part which isn't specular, i.e. can be connected
+    connectible: bool,
+}
+
+impl Vertex {
+    // scattering function times cosine for light arriving along incoming, leaving along outgoing
+    fn eval(&self, incoming: &Vec3, outgoing: &Vec3, scene: &Scene<'_>) -> Vec3 {
+        match (&self.hit, &self.material) {
+            (Some(hit), Some(material)) => {
+                material.eval(&scene.ray(&self.point - incoming, incoming.clone()), hit,
+                              &scene.ray(self.point.clone(), outgoing.clone()))
+            },
+            _ => Vec3::default(),
+        }
+    }
+
+    // density by solid angle of scattering light arriving along incoming into outgoing
+    fn pdf(&self, incoming: &Vec3, outgoing: &Vec3, scene: &Scene<'_>) -> f64 {
+        match (&self.hit, &self.material) {
+            (Some(hit), Some(material)) if self.connectible => {
+                material.pdf(&scene.ray(&self.point - incoming, incoming.clone()), hit,
+                             &scene.ray(self.point.clone(), outgoing.clone()))
+            },
+            _ => 0.0,
+        }
+    }
+
+    fn emitted(&self) -> Option<Vec3> {
+        let hit = self.hit.as_ref()?;
+        self.material.as_ref()?.emitted(hit.u, hit.v, &hit.point)
+    }
+
+    fn direction_to(&self, other: &Point3) -> Vec3 {
+        (other - &self.point).unit()
+    }
+}
+
+
+// converts a density by solid angle at `from` into a density by area at `to`
+fn to_area(pdf: f64, from: &Point3, to: &Point3, to_normal: &Vec3) -> f64 {
+    let d = to - from;
+    let distance_squared = d.length_squared();
+    if distance_squared <= 0.0 {
+        return 0.0;
+    }
+
+    pdf * d.dot(to_normal).abs() / (distance_squared * distance_squared.sqrt())
+}
+
+
+// lights emit on both sides of their surface with a cosine distribution, like `DiffuseLight`
+fn emission_pdf(normal: &Vec3, direction: &Vec3) -> f64 {
+    0.5 * normal.dot(direction).abs() / PI
+}
+
+
+// state of a single camera sample
+struct Scene<'w> {
+    world: &'w World,
+    // the lights with a surface, only these can start light subpaths
+    emitters: Vec<&'w Arc<dyn Hittable>>,
+    time: f64,
+    wavelength: Option<f64>,
+}
+
+impl Scene<'_> {
+    const fn ray(&self, origin: Point3, direction: Vec3) -> Ray {
+        Ray::new(origin, direction, self.time).with_wavelength(self.wavelength)
+    }
+
+    // density by solid angle of light sampling, equal to `HittableList::pdf_value()`
+    #[allow(clippy::cast_precision_loss)]
+    fn light_pdf(&self, origin: &Point3, direction: &Vec3) -> f64 {
+        if self.emitters.is_empty() {
+            return 0.0;
+        }
+
+        self.emitters.iter().map(|light| light.pdf_value(origin, direction)).sum::<f64>()
+            / self.emitters.len() as f64
+    }
+
+    /**
+     * Density by area of a point on a light as start of a light subpath. The
+     * light is found by looking at the point from just above the surface.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn origin_pdf(&self, point: &Point3, normal: &Vec3, rng: &mut FastRng) -> f64 {
+        let probe = Ray::new(point + normal * 0.001, -normal, self.time);
+
+        self.emitters.iter()
+            .find(|light| light.hit(&probe, &(0.0 .. 0.01)).is_some())
+            .and_then(|light| light.sample_surface(rng))
+            .map_or(0.0, |sample| 1.0 / (self.emitters.len() as f64 * sample.area))
+    }
+
+    fn visible(&self, from: &Point3, to: &Point3) -> bool {
+        let d = to - from;
+        let distance = d.length();
+        let ray = self.ray(from.clone(), d / distance);
+
+        self.world.hit(&ray, &(0.001 .. distance - 0.001)).is_none()
+    }
+}
+
+
+// densities of a vertex of a full path, ordered from the light to the camera
+struct Node {
+    point: Point3,
+    normal: Vec3,
+    // density when sampled by the light subpath
+    pdf_light: f64,
+    // density when sampled by the camera subpath
+    pdf_camera: f64,
+    delta: bool,
+}
+
+
+/**
+ * A bidirectional path tracer. For every camera sample it traces a path from
+ * the camera and another one from a random point on a light, then connects
+ * every vertex of the camera path with every vertex of the light path by a
+ * shadow ray. Each connection is weighted by the balance heuristic against
+ * all other connections which could have produced the same path, which
+ * includes hitting a light by chance and light sampling.
+ *
+ * This finds light which is hard to reach from the camera side, e.g. light
+ * falling through a gap or reflected by a rough metal before it lights the
+ * scene. Light paths are never connected to the camera itself, so caustics
+ * seen on diffuse surfaces still come from paths which hit the light.
+ *
+ * Point, directional and spot lights and the background are only found by
+ * the camera path. Materials take part in connections by `Material::eval()`
+ * and `Material::pdf()`.
+ */
+#[derive(Debug, Clone)]
+pub struct Bdpt {
+    depth: usize,
+    russian_roulette_depth: usize,
+}
+
+impl Default for Bdpt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bdpt {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 64,
+            russian_roulette_depth: 3,
+        }
+    }
+
+    /// Hard limit for the number of vertices of the camera and of the light path
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Subpaths are terminated randomly by russian roulette once they reached this depth
+    #[must_use]
+    pub const fn russian_roulette(mut self, min_depth: usize) -> Self {
+        self.russian_roulette_depth = min_depth;
+        self
+    }
+
+    /**
+     * Extends the subpath along the ray until it leaves the scene, hits a
+     * surface which doesn't scatter or ends by russian roulette. Returns the
+     * light found without connection, i.e. the background.
+     */
+    #[allow(clippy::too_many_lines)]
+    fn walk(&self, scene: &Scene<'_>, mut ray: Ray, rng: &mut FastRng, path: &mut Vec<Vertex>) -> Vec3 {
+
+        // a light subpath leaves the surface of the light in a cosine weighted
+        // direction on either side, which carries 2 pi times the emission
+        let (mut beta, mut pdf_dir) = match path.last() {
+            Some(origin) => (&origin.beta * (2.0 * PI), emission_pdf(&origin.normal, &ray.direction.unit())),
+            None => (Vec3::new(1.0, 1.0, 1.0), 1.0),
+        };
+        let beta_max = beta.x.max(beta.y).max(beta.z);
+
+        for bounce in 0 .. self.depth {
+            let Some(hit) = scene.world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+                let background: Vec3 = scene.world.background(&ray).into();
+                return beta * background;
+            };
+
+            let material = material_at(scene.world, &hit);
+
+            let mut vertex = Vertex {
+                point: hit.point.clone(),
+                normal: hit.normal.clone(),
+                hit: None,
+                material: Some(material.clone()),
+                incoming: ray.direction.unit(),
+                beta: beta.clone(),
+                pdf_fwd: 1.0,
+                pdf_rev: 0.0,
+                delta: false,
+                connectible: true,
+            };
+            if let Some(previous) = path.last() {
+                vertex.pdf_fwd = to_area(pdf_dir, &previous.point, &vertex.point, &vertex.normal);
+            }
+
+            let sample = match material.scatter(&ray, &hit) {
+                Some(srec) if srec.skip_pdf && srec.ray.is_none() => {
+                    // dead end material, doesn't scatter light
+                    let color = Vec3::from(srec.color) * &beta;
+                    vertex.hit = Some(hit);
+                    path.push(vertex);
+                    return color;
+                },
+                Some(srec) => sample_material(&ray, &hit, material.as_ref(), srec, rng),
+                None => None,
+            };
+
+            let Some(sample) = sample else {
+                vertex.hit = Some(hit);
+                path.push(vertex);
+                break;
+            };
+
+            // a specular sample of e.g. a mixed material leaves the other
+            // parts of the material, which can still be connected
+            let scattered = sample.ray;
+            let direction = scattered.direction.unit();
+            vertex.delta = sample.pdf <= 0.0;
+            vertex.connectible = sample.pdf > 0.0 || material.pdf(&ray, &hit, &scattered) > 0.0;
+            vertex.hit = Some(hit);
+
+            // the density of the previous vertex when sampled from here, in reverse direction
+            if let Some(previous) = path.last_mut() {
+                let pdf_rev = vertex.pdf(&-&direction, &vertex.direction_to(&previous.point), scene);
+                previous.pdf_rev = to_area(pdf_rev, &vertex.point, &previous.point, &previous.normal);
+            }
+
+            path.push(vertex);
+
+            beta = sample.weight * &beta;
+            pdf_dir = sample.pdf;
+            ray = scattered;
+
+            if bounce + 1 >= self.russian_roulette_depth {
+                let survive = (beta.x.max(beta.y).max(beta.z) / beta_max).min(1.0);
+
+                if survive <= 0.0 || survive.is_nan() || rng.gen() >= survive {
+                    break;
+                }
+
+                beta /= survive;
+            }
+        }
+
+        Vec3::default()
+    }
+
+    // starts a path at a random point of a random light
+    #[allow(clippy::cast_precision_loss)]
+    fn light_path(&self, scene: &Scene<'_>, rng: &mut FastRng) -> Vec<Vertex> {
+        let mut path = Vec::new();
+        if scene.emitters.is_empty() || self.depth == 0 {
+            return path;
+        }
+
+        let light = scene.emitters[rng.irange(0, scene.emitters.len())];
+        let Some(sample) = light.sample_surface(rng) else {
+            return path;
+        };
+
+        let normal = if rng.gen() < 0.5 { sample.normal } else { -sample.normal };
+
+        // look at the light from just above the point to find its emission
+        let probe = Ray::new(&sample.point + &normal * 0.001, -&normal, scene.time);
+        let emitted = light
+            .hit(&probe, &(0.0 .. 0.01))
+            .and_then(|hit| hit.material.as_ref().and_then(|m| m.emitted(hit.u, hit.v, &hit.point)));
+
+        let Some(emitted) = emitted else {
+            return path;
+        };
+
+        let direction = ONB::build_from(&normal).local(&Vec3::random_cosine_direction(rng));
+
+        path.push(Vertex {
+            pdf_fwd: scene.origin_pdf(&sample.point, &normal, rng),
+            point: sample.point.clone(),
+            normal,
+            hit: None,
+            material: None,
+            incoming: Vec3::default(),
+            beta: emitted * (scene.emitters.len() as f64 * sample.area),
+            pdf_rev: 0.0,
+            delta: false,
+            connectible: true,
+        });
+
+        let ray = scene.ray(sample.point, direction);
+        self.walk(scene, ray, rng, &mut path);
+
+        path
+    }
+
+    /**
+     * Balance heuristic weight of the strategy which took `s` vertices from
+     * the light subpath. `nee` is the density by area of the light vertex
+     * when found by light sampling from its neighbour.
+     */
+    fn mis_weight(&self, nodes: &mut [Node], s: usize, nee: f64) -> f64 {
+        let n = nodes.len();
+
+        // densities at delta vertices are not defined, they cancel in all strategies
+        for i in 0 .. n {
+            if i > 0 && nodes[i - 1].delta {
+                nodes[i].pdf_light = 1.0;
+            }
+            if i + 1 < n && nodes[i + 1].delta {
+                nodes[i].pdf_camera = 1.0;
+            }
+        }
+
+        let density = |k: usize| -> f64 {
+            // subpaths are limited, and connections need two vertices which aren't delta
+            if n - k > self.depth || k > self.depth + 1 {
+                return 0.0;
+            }
+            if (k > 0 && nodes[k - 1].delta) || nodes[k].delta {
+                return 0.0;
+            }
+
+            nodes.iter().enumerate().fold(1.0, |p, (i, node)| {
+                if i >= k {
+                    p * node.pdf_camera
+                }
+                else if i == 0 && k == 1 {
+                    p * nee
+                }
+                else {
+                    p * node.pdf_light
+                }
+            })
+        };
+
+        // the camera itself is never connected, so at least one vertex is from the camera
+        let own = density(s);
+        let total: f64 = (0 .. n).map(density).sum();
+
+        if own > 0.0 && total > 0.0 { own / total } else { 0.0 }
+    }
+
+    fn node(vertex: &Vertex, camera: bool) -> Node {
+        Node {
+            point: vertex.point.clone(),
+            normal: vertex.normal.clone(),
+            pdf_light: if camera { vertex.pdf_rev } else { vertex.pdf_fwd },
+            pdf_camera: if camera { vertex.pdf_fwd } else { vertex.pdf_rev },
+            delta: vertex.delta,
+        }
+    }
+
+    // nodes of the camera subpath, in light to camera order
+    fn camera_nodes(camera: &[Vertex], nodes: &mut Vec<Node>) {
+        nodes.extend(camera.iter().rev().map(|vertex| Self::node(vertex, true)));
+    }
+
+    // density of light sampling for the first node from the second
+    fn nee_pdf(scene: &Scene<'_>, nodes: &[Node]) -> f64 {
+        if nodes.len() < 2 {
+            return 0.0;
+        }
+
+        let (light, from) = (&nodes[0], &nodes[1]);
+        let direction = (&light.point - &from.point).unit();
+        to_area(scene.light_pdf(&from.point, &direction), &from.point, &light.point, &light.normal)
+    }
+
+    // the camera path hit a light with its last vertex
+    fn hit_light(&self, scene: &Scene<'_>, camera: &[Vertex], rng: &mut FastRng) -> Vec3 {
+        let t = camera.len();
+        let vertex = &camera[t - 1];
+        let Some(emitted) = vertex.emitted() else {
+            return Vec3::default();
+        };
+
+        let mut nodes = Vec::with_capacity(t);
+        Self::camera_nodes(camera, &mut nodes);
+        // the path ends on the light, whatever lobe its last vertex sampled
+        nodes[0].delta = false;
+        nodes[0].pdf_light = scene.origin_pdf(&vertex.point, &vertex.normal, rng);
+        if t >= 2 {
+            let previous = &camera[t - 2];
+            let pdf = emission_pdf(&vertex.normal, &vertex.direction_to(&previous.point));
+            nodes[1].pdf_light = to_area(pdf, &vertex.point, &previous.point, &previous.normal);
+        }
+
+        let nee = Self::nee_pdf(scene, &nodes);
+        emitted * &vertex.beta * self.mis_weight(&mut nodes, 0, nee)
+    }
+
+    // light sampling from the last vertex of the camera path
+    fn sample_light(&self, scene: &Scene<'_>, camera: &[Vertex], rng: &mut FastRng) -> Vec3 {
+        let t = camera.len();
+        let vertex = &camera[t - 1];
+
+        let light = scene.emitters[rng.irange(0, scene.emitters.len())];
+        let direction = light.random(&vertex.point, rng).unit();
+        let pdf = scene.light_pdf(&vertex.point, &direction);
+        if pdf <= 0.0 || pdf.is_nan() {
+            return Vec3::default();
+        }
+
+        let f = vertex.eval(&vertex.incoming, &direction, scene);
+        if f.length_squared() <= 0.0 {
+            return Vec3::default();
+        }
+
+        let ray = scene.ray(vertex.point.clone(), direction.clone());
+        let Some(hit) = scene.world.hit(&ray, &(0.001 .. f64::INFINITY)) else {
+            return Vec3::default();
+        };
+        let Some(emitted) = hit.material.as_ref().and_then(|m| m.emitted(hit.u, hit.v, &hit.point)) else {
+            return Vec3::default();
+        };
+
+        let mut nodes = Vec::with_capacity(t + 1);
+        nodes.push(Node {
+            pdf_light: scene.origin_pdf(&hit.point, &hit.normal, rng),
+            pdf_camera: to_area(vertex.pdf(&vertex.incoming, &direction, scene), &vertex.point, &hit.point, &hit.normal),
+            point: hit.point.clone(),
+            normal: hit.normal.clone(),
+            delta: false,
+        });
+        Self::camera_nodes(camera, &mut nodes);
+        // the camera vertex scatters towards the light, not along its sampled lobe
+        nodes[1].delta = false;
+
+        let pdf_light = emission_pdf(&hit.normal, &direction);
+        nodes[1].pdf_light = to_area(pdf_light, &hit.point, &vertex.point, &vertex.normal);
+        if t >= 2 {
+            let previous = &camera[t - 2];
+            let pdf = vertex.pdf(&direction, &vertex.direction_to(&previous.point), scene);
+            nodes[2].pdf_light = to_area(pdf, &vertex.point, &previous.point, &previous.normal);
+        }
+
+        let nee = Self::nee_pdf(scene, &nodes);
+        emitted * f * &vertex.beta * (self.mis_weight(&mut nodes, 1, nee) / pdf)
+    }
+
+    // connects the last vertices of the light path and the camera path
+    fn connect(&self, scene: &Scene<'_>, light: &[Vertex], camera: &[Vertex]) -> Vec3 {
+        let (s, t) = (light.len(), camera.len());
+        let (x, y) = (&light[s - 1], &camera[t - 1]);
+        if !x.connectible || !y.connectible {
+            return Vec3::default();
+        }
+
+        let to_light = y.direction_to(&x.point);
+        let distance_squared = (&x.point - &y.point).length_squared();
+
+        let f = x.eval(&x.incoming, &-&to_light, scene) * y.eval(&y.incoming, &to_light, scene);
+        let contribution = f * &x.beta * &y.beta / distance_squared;
+        if contribution.length_squared() <= 0.0 || !scene.visible(&y.point, &x.point) {
+            return Vec3::default();
+        }
+
+        let mut nodes = Vec::with_capacity(s + t);
+        nodes.extend(light.iter().map(|vertex| Self::node(vertex, false)));
+        Self::camera_nodes(camera, &mut nodes);
+
+        // the ends of the connection scatter towards each other, not along their sampled lobes
+        nodes[s - 1].delta = false;
+        nodes[s].delta = false;
+
+        // the densities at the connection follow from the other side
+        nodes[s - 1].pdf_camera = to_area(y.pdf(&y.incoming, &to_light, scene), &y.point, &x.point, &x.normal);
+        let previous = &light[s - 2];
+        let pdf = x.pdf(&to_light, &x.direction_to(&previous.point), scene);
+        nodes[s - 2].pdf_camera = to_area(pdf, &x.point, &previous.point, &previous.normal);
+
+        nodes[s].pdf_light = to_area(x.pdf(&x.incoming, &-&to_light, scene), &x.point, &y.point, &y.normal);
+        if t >= 2 {
+            let previous = &camera[t - 2];
+            let pdf = y.pdf(&-&to_light, &y.direction_to(&previous.point), scene);
+            nodes[s + 1].pdf_light = to_area(pdf, &y.point, &previous.point, &previous.normal);
+        }
+
+        let nee = Self::nee_pdf(scene, &nodes);
+        contribution * self.mis_weight(&mut nodes, s, nee)
+    }
+
+    // point, directional and spot lights are only reached by shadow rays from the camera path
+    fn delta_light_color(scene: &Scene<'_>, vertex: &Vertex) -> Vec3 {
+        match (&vertex.hit, &vertex.material) {
+            (Some(hit), Some(material)) => {
+                let ray = scene.ray(&vertex.point - &vertex.incoming, vertex.incoming.clone());
+                delta_light_color(scene.world, &ray, hit, material.as_ref()) * &vertex.beta
+            },
+            _ => Vec3::default(),
+        }
+    }
+}
+
+
+impl Integrator for Bdpt {
+    fn ray_color(&self, ray: Ray, world: &World, rng: &mut FastRng) -> Vec3 {
+        let scene = Scene {
+            world,
+            emitters: world.lights.iter().filter(|light| light.emissive()).collect(),
+            time: ray.departure_time,
+            wavelength: ray.wavelength,
+        };
+
+        let mut camera = Vec::with_capacity(self.depth);
+        let mut color = self.walk(&scene, ray, rng, &mut camera);
+        let light = self.light_path(&scene, rng);
+
+        for t in 1 ..= camera.len() {
+            color += self.hit_light(&scene, &camera[.. t], rng);
+
+            let vertex = &camera[t - 1];
+            if !vertex.connectible {
+                continue;
+            }
+
+            color += Self::delta_light_color(&scene, vertex);
+
+            if !scene.emitters.is_empty() {
+                color += self.sample_light(&scene, &camera[.. t], rng);
+            }
+
+            for s in 2 ..= light.len() {
+                color += self.connect(&scene, &light[.. s], &camera[.. t]);
+            }
+        }
+
+        color
+    }
+}
@@ -0,0 +1,271 @@
+use {
+    crate::{
+        hittable::{
+            collection::World,
+            HitRecord, Hittable,
+        },
+        integrator::{material_at, Integrator},
+        material::Material,
+        prelude::*,
+    },
+    std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    },
+};
+
+
+// fully saturated color of the hue in range 0 .. 1, red at 0, green at 1/3, blue at 2/3
+fn hue(h: f64) -> Vec3 {
+    let h = h.rem_euclid(1.0) * 6.0;
+    let channel = |offset: f64| (((h + offset) % 6.0 - 3.0).abs() - 1.0).clamp(0.0, 1.0);
+    Vec3::new(channel(0.0), channel(4.0), channel(2.0))
+}
+
+
+/**
+ * Shows the surface normals of the first hit as colors, the x, y and z
+ * components from -1 .. 1 mapped to red, green and blue in 0 .. 1. Normals
+ * point out of the object, so the inside of a surface has other colors than
+ * its outside.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Normals;
+
+impl Integrator for Normals {
+    fn ray_color(&self, ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        match world.hit(&ray, &(0.0001 .. f64::INFINITY)) {
+            Some(hit) => {
+                let normal = if hit.outside { hit.normal } else { -hit.normal };
+                (normal + Vec3::new(1.0, 1.0, 1.0)) * 0.5
+            },
+            None => Vec3::default(),
+        }
+    }
+}
+
+
+/**
+ * The color of the surfaces without any lighting. Mirrors and glass are
+ * followed up to the first surface which scatters diffusely. Lights show
+ * their emission, rays which miss the scene the background.
+ */
+#[derive(Debug, Clone)]
+pub struct Albedo {
+    depth: usize,
+}
+
+impl Default for Albedo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Albedo {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 8,
+        }
+    }
+}
+
+impl Integrator for Albedo {
+    fn ray_color(&self, mut ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+
+        for _ in 0 .. self.depth {
+            let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+                let background: Vec3 = world.background(&ray).into();
+                return throughput * background;
+            };
+
+            let material = material_at(world, &hit);
+            if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+                return throughput * emitted;
+            }
+
+            let Some(srec) = material.scatter(&ray, &hit) else {
+                break;
+            };
+
+            // the weight of a diffuse scattering record is the reflectance
+            if !srec.skip_pdf {
+                return srec.color * &throughput;
+            }
+
+            match srec.ray {
+                Some(scattered) => {
+                    throughput = srec.color * &throughput;
+                    ray = scattered.with_wavelength(ray.wavelength);
+                },
+                None => break,
+            }
+        }
+
+        Vec3::default()
+    }
+}
+
+
+/**
+ * Shows the texture coordinates of the first hit, u as red and v as green.
+ * Seams and distortions of textures show up as jumps and stretched
+ * gradients.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Uvs;
+
+impl Integrator for Uvs {
+    fn ray_color(&self, ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        match world.hit(&ray, &(0.0001 .. f64::INFINITY)) {
+            Some(hit) => Vec3::new(hit.u, hit.v, 0.0),
+            None => Vec3::default(),
+        }
+    }
+}
+
+
+/**
+ * Shows the distance to the first hit, near is white and the maximum
+ * distance black. By default the maximum distance is the size of the scene.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Depth {
+    max_distance: Option<f64>,
+}
+
+impl Depth {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_distance: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn max_distance(mut self, distance: f64) -> Self {
+        self.max_distance = Some(distance);
+        self
+    }
+}
+
+impl Integrator for Depth {
+    fn ray_color(&self, ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+            return Vec3::default();
+        };
+
+        let max_distance = self.max_distance.unwrap_or_else(|| {
+            world.bbox(&(0.0 .. 0.0)).map_or(1.0, |bbox| (&bbox.max - &bbox.min).length())
+        });
+
+        let value = (1.0 - hit.t1 * ray.direction.length() / max_distance).clamp(0.0, 1.0);
+        Vec3::new(value, value, value)
+    }
+}
+
+
+/**
+ * Heatmap of the bounding boxes which are tested to find the first hit,
+ * from blue for none to red for the given maximum. Hot spots are where
+ * the bounding volume hierarchy serves badly, e.g. at long thin objects.
+ */
+#[derive(Debug, Clone)]
+pub struct BvhCost {
+    max_tests: usize,
+}
+
+impl Default for BvhCost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BvhCost {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_tests: 100,
+        }
+    }
+
+    /// The number of bounding box tests which is shown red
+    #[must_use]
+    pub const fn max_tests(mut self, tests: usize) -> Self {
+        self.max_tests = tests;
+        self
+    }
+}
+
+impl Integrator for BvhCost {
+    #[allow(clippy::cast_precision_loss)]
+    fn ray_color(&self, ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        let tests = world.box_tests(&ray, &(0.0001 .. f64::INFINITY));
+
+        let heat = (tests as f64 / self.max_tests.max(1) as f64).min(1.0);
+        hue((1.0 - heat) * 2.0 / 3.0)
+    }
+}
+
+
+// feeds the bits of the components to the hasher, equal vectors give equal hashes
+fn hash_vec(v: &Vec3, hasher: &mut DefaultHasher) {
+    for c in [v.x, v.y, v.z] {
+        c.to_bits().hash(hasher);
+    }
+}
+
+/**
+ * Fingerprint of a material from its response at a fixed probe hit: the
+ * scattered color and direction, the reflected light for a fixed light
+ * direction and the emission. Materials scatter deterministically for a
+ * given hit, so the fingerprint is the same in every run.
+ */
+fn fingerprint(material: &dyn Material) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    let ray = Ray::new(Point3::new(0.3, 0.2, 1.0), Vec3::new(-0.3, -0.2, -1.0), 0.0);
+    let t = ray.direction.length();
+    let hit = HitRecord::with_normal(Point3::default(), Vec3::new(0.0, 0.0, 1.0), None, (0.37, 0.61), t, t);
+
+    if let Some(srec) = material.scatter(&ray, &hit) {
+        hash_vec(&srec.color.into(), &mut hasher);
+        if let Some(scattered) = srec.ray {
+            hash_vec(&scattered.direction, &mut hasher);
+        }
+    }
+
+    let to_light = Ray::new(hit.point.clone(), Vec3::new(0.2, -0.1, 1.0).unit(), 0.0);
+    hash_vec(&material.eval(&ray, &hit, &to_light), &mut hasher);
+
+    if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+        hash_vec(&emitted, &mut hasher);
+    }
+
+    hasher.finish()
+}
+
+
+/**
+ * Gives each material its own color, so objects which share a material
+ * have the same color. Objects without material are gray. The colors are
+ * derived from how the materials respond to a fixed probe, so they are the
+ * same between runs, and materials with the same settings share a color.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct MaterialId;
+
+impl Integrator for MaterialId {
+    #[allow(clippy::cast_precision_loss)]
+    fn ray_color(&self, ray: Ray, world: &World, _rng: &mut FastRng) -> Vec3 {
+        let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+            return Vec3::default();
+        };
+
+        hit.material.map_or_else(|| Vec3::new(0.5, 0.5, 0.5), |material| {
+            let h = fingerprint(material.as_ref()) >> 40;
+            hue(h as f64 / f64::from(1 << 24))
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::{
+        area_light_color, delta_light_color, incoming_light, material_at, power_heuristic, sample_material,
+        Integrator,
+    },
+    prelude::*,
+};
+
+
+/**
+ * Only the light which reaches a surface directly from a light source, there
+ * is no indirect light. Mirrors and glass are followed, so reflections and
+ * refractions show the directly lit scene.
+ *
+ * At the first diffuse or glossy surface one light sample and one material
+ * sample are taken, weighted by multiple importance sampling. This is much
+ * faster than path tracing and shows the placement of lights and shadows.
+ */
+#[derive(Debug, Clone)]
+pub struct DirectLighting {
+    depth: usize,
+}
+
+impl Default for DirectLighting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirectLighting {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 16,
+        }
+    }
+
+    /// Limit for the number of mirror and glass bounces
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+impl Integrator for DirectLighting {
+    /**
+     * Every hit gets the light sample, which only needs `eval()` and `pdf()`
+     * of the material. Specular material samples are followed, the first
+     * other one ends the path with the light it finds, weighted against
+     * the light sample.
+     */
+    fn ray_color(&self, mut ray: Ray, world: &World, rng: &mut FastRng) -> Vec3 {
+        let mut color = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+
+        for _ in 0 .. self.depth {
+            let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+                let background: Vec3 = world.background(&ray).into();
+                return color + throughput * background;
+            };
+
+            let material = material_at(world, &hit);
+            if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+                color += &throughput * emitted;
+            }
+
+            color += &throughput * (delta_light_color(world, &ray, &hit, material.as_ref())
+                + area_light_color(world, &ray, &hit, material.as_ref(), rng));
+
+            let sample = material
+                .scatter(&ray, &hit)
+                .and_then(|srec| sample_material(&ray, &hit, material.as_ref(), srec, rng));
+            let Some(sample) = sample else {
+                break;
+            };
+
+            // mirrors and glass are followed, they can't be lit directly
+            if sample.pdf <= 0.0 {
+                throughput = sample.weight * &throughput;
+                ray = sample.ray;
+                continue;
+            }
+
+            let light_pdf = world.lights.pdf_value(&sample.ray.origin, &sample.ray.direction.unit());
+            let weight = power_heuristic(sample.pdf, light_pdf);
+            color += &throughput * sample.weight * incoming_light(world, &sample.ray) * weight;
+            break;
+        }
+
+        color
+    }
+}
@@ -0,0 +1,329 @@
+use {
+    crate::{
+        hittable::{collection::World, HitRecord, Hittable},
+        material::{CommonMaterialSettings, Material, ScatterRecord},
+        prelude::*,
+    },
+    std::{fmt::Debug, sync::Arc},
+};
+
+pub(crate) mod ambient_occlusion;
+pub(crate) mod bdpt;
+pub(crate) mod debug;
+pub(crate) mod direct;
+pub(crate) mod path;
+pub(crate) mod whitted;
+
+pub use {
+    ambient_occlusion::AmbientOcclusion,
+    bdpt::Bdpt,
+    debug::{Albedo, BvhCost, Depth, MaterialId, Normals, Uvs},
+    direct::DirectLighting,
+    path::PathTracer,
+    whitted::Whitted,
+};
+
+
+/**
+ * Computes the light arriving at the camera along a ray. The painter asks
+ * the integrator for every sample of every pixel, so it must be usable from
+ * all render threads at once.
+ */
+pub trait Integrator: Debug + Send + Sync {
+    fn ray_color(&self, ray: Ray, world: &World, rng: &mut FastRng) -> Vec3;
+}
+
+
+/// The material at the hit, objects without material use the world's default
+pub(crate) fn material_at(world: &World, hit: &HitRecord) -> Arc<dyn Material> {
+    match hit.material {
+        Some(ref m) => m.clone(),
+        None => world.default_material.clone(),
+    }
+}
+
+
+/**
+ * The phong highlight of the material settings, as a factor on top of the
+ * light which the material reflects from the direction to the light.
+ */
+pub(crate) fn phong_highlight(dir_to_light: &Vec3, ray_dir: &Vec3, normal: &Vec3, settings: &CommonMaterialSettings) -> f64 {
+    if settings.phong_factor <= 0.0 {
+        return 0.0;
+    }
+
+    let from_light = -dir_to_light;
+    let reflected = &from_light - 2.0 * from_light.dot(normal) * normal;
+    let specular = reflected.dot(&-ray_dir).max(0.0).powi(settings.phong_exponent);
+
+    specular * settings.phong_factor
+}
+
+
+/**
+ * Light from point, directional and spot lights which the material scatters
+ * from the hit towards the origin of the ray, with the phong highlight of
+ * the material. These lights can't be hit by rays, so each of them is
+ * checked with a shadow ray.
+ */
+pub(crate) fn delta_light_color(world: &World, ray: &Ray, hit: &HitRecord, material: &dyn Material) -> Vec3 {
+    let mut color = Vec3::default();
+    let settings = material.settings();
+
+    for light in &world.delta_lights {
+        let Some(sample) = light.illuminate(&hit.point) else {
+            continue;
+        };
+
+        let shadow_ray = Ray::new(hit.point.clone(), sample.direction.clone(), ray.departure_time)
+            .with_wavelength(ray.wavelength);
+
+        let f = material.eval(ray, hit, &shadow_ray);
+        if f.length_squared() <= 0.0 || world.hit(&shadow_ray, &(0.001 .. sample.distance)).is_some() {
+            continue;
+        }
+
+        let highlight = 1.0 + phong_highlight(&sample.direction, &ray.direction, &hit.normal, &settings);
+        color += f * sample.irradiance * highlight;
+    }
+
+    color
+}
+
+
+/**
+ * One sample of the lights with a surface, weighted by multiple importance
+ * sampling against the material sampling the same direction. The material
+ * sample is the caller's, see `power_heuristic()`.
+ */
+pub(crate) fn area_light_color(world: &World, ray: &Ray, hit: &HitRecord, material: &dyn Material,
+                               rng: &mut FastRng) -> Vec3 {
+    if world.lights.is_empty() {
+        return Vec3::default();
+    }
+
+    let start = ray.at(hit.t1 - 0.0002);
+    let direction = world.lights.random(&start, rng).unit();
+    let light_pdf = world.lights.pdf_value(&start, &direction);
+    let light_ray = Ray::new(start, direction, ray.departure_time).with_wavelength(ray.wavelength);
+
+    let f = material.eval(ray, hit, &light_ray);
+    if light_pdf <= 0.0 || f.length_squared() <= 0.0 {
+        return Vec3::default();
+    }
+
+    let weight = power_heuristic(light_pdf, material.pdf(ray, hit, &light_ray)) / light_pdf;
+    f * incoming_light(world, &light_ray) * weight
+}
+
+
+/**
+ * A ray scattered by the material with its path weight, the scattering
+ * function times cosine per density, and the density of its direction.
+ * The density is zero for single direction (specular) scattering, which
+ * light sampling can't find.
+ */
+pub(crate) struct MaterialSample {
+    pub ray: Ray,
+    pub weight: Vec3,
+    pub pdf: f64,
+}
+
+/**
+ * The ray of the scattering record, drawn from its pdf if the material
+ * didn't pick the direction itself.
+ */
+pub(crate) fn sample_material(ray: &Ray, hit: &HitRecord, material: &dyn Material, srec: ScatterRecord,
+                              rng: &mut FastRng) -> Option<MaterialSample> {
+    let scattered = match srec.ray {
+        Some(scattered) => scattered,
+        None if srec.skip_pdf => return None,
+        // we must trace back some distance, a beam to the light source can easily
+        // hit the same body again, but if the ray starts to close, the intersection
+        // will be ignored
+        None => Ray::new(ray.at(hit.t1 - 0.0002), srec.pdf.generate(rng), ray.departure_time),
+    };
+    let scattered = scattered.with_wavelength(ray.wavelength);
+
+    if srec.skip_pdf {
+        return Some(MaterialSample {
+            ray: scattered,
+            weight: srec.color.into(),
+            pdf: 0.0,
+        });
+    }
+
+    // all lobes of the material together could have picked the direction
+    let pdf = material.pdf(ray, hit, &scattered);
+    if pdf <= 0.0 {
+        return None;
+    }
+
+    Some(MaterialSample {
+        ray: scattered,
+        weight: srec.color.into(),
+        pdf,
+    })
+}
+
+
+/// Weight of a sample of the first strategy, against the second one
+pub(crate) fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0.0 { a / (a + b) } else { 0.0 }
+}
+
+
+/**
+ * Light arriving along the ray, from an emitting surface or from the
+ * background. The ray is not followed any further.
+ */
+pub(crate) fn incoming_light(world: &World, ray: &Ray) -> Vec3 {
+    match world.hit(ray, &(0.001 .. f64::INFINITY)) {
+        Some(hit) => material_at(world, &hit)
+            .emitted(hit.u, hit.v, &hit.point)
+            .unwrap_or_default(),
+        None => world.background(ray).into(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            hittable::{collection::HittableList, geometry::Triangle, Sphere},
+            light::PointLight,
+            material::{AnisotropicMetal, Cutout, DiffuseLight, GgxConductor, Lambertian, MixedMaterial, Principled, ThinFilm},
+            texture::Texture,
+        },
+    };
+
+    fn black(_ray: &Ray) -> Color {
+        Color::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    fn gray() -> Arc<dyn Texture> {
+        Arc::new(Color::new(0.8, 0.8, 0.8, 1.0))
+    }
+
+    // a sphere of the material at the origin, lit by a point light only
+    fn point_lit(material: Arc<dyn Material>) -> World {
+        let mut list = HittableList::default();
+        list.add(Sphere::new(Point3::default(), 1.0, Some(material)));
+
+        let mut world = World::new(list, HittableList::default(), black, &(0.0 .. 0.0));
+        world.add_light(PointLight::new(Point3::new(1.0, 1.5, 5.0), Color::new(10.0, 10.0, 10.0, 1.0)));
+        world
+    }
+
+    // mean of many samples, the rays differ slightly so that the
+    // materials make different choices
+    fn render(integrator: &dyn Integrator, world: &World, samples: u32) -> Vec3 {
+        let mut rng = FastRng::new_seeded(7);
+        let mut color = Vec3::default();
+
+        for i in 0 .. samples {
+            let origin = Point3::new(0.3 + f64::from(i) * 1e-9, 0.4, 5.0);
+            let ray = Ray::new(origin, Vec3::new(-0.1, -0.1, -1.0).unit(), 0.0);
+            color += integrator.ray_color(ray, world, &mut rng);
+        }
+
+        color / f64::from(samples)
+    }
+
+    fn assert_close(world: &World, integrators: &[&dyn Integrator], samples: u32, tolerance: f64) {
+        let expected = render(&DirectLighting::new(), world, samples);
+        assert!(expected.length() > 0.0);
+
+        for integrator in integrators {
+            let found = render(*integrator, world, samples);
+            assert!((&found - &expected).length() <= tolerance * expected.length(),
+                    "{:?} gives {:?}, direct lighting {:?}", integrator, found, expected);
+        }
+    }
+
+    // direct lighting is exact for a single object under point lights
+    fn assert_agree(world: &World, integrators: &[&dyn Integrator]) {
+        assert_close(world, integrators, 1000, 0.01);
+    }
+
+    #[test]
+    fn mixed_material_under_point_light() {
+        let diffuse = Arc::new(Lambertian::new(gray()));
+        let glossy = Arc::new(GgxConductor::new(gray(), 0.4));
+        let world = point_lit(Arc::new(MixedMaterial::new(diffuse, glossy, 0.5)));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    #[test]
+    fn ggx_under_point_light() {
+        let world = point_lit(Arc::new(GgxConductor::new(gray(), 0.4)));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    #[test]
+    fn anisotropic_metal_under_point_light() {
+        let world = point_lit(Arc::new(AnisotropicMetal::new(gray(), 0.3, 0.6)));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    #[test]
+    fn principled_under_point_light() {
+        let world = point_lit(Arc::new(Principled::new(gray())));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    #[test]
+    fn metallic_principled_under_point_light() {
+        let world = point_lit(Arc::new(Principled::new(gray()).metallic(1.0)));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    #[test]
+    fn thin_film_under_point_light() {
+        let diffuse = Arc::new(Lambertian::new(gray()));
+        let world = point_lit(Arc::new(ThinFilm::new(diffuse, 400.0, 1.4)));
+
+        assert_agree(&world, &[&PathTracer::new(), &Whitted::new(), &Bdpt::new()]);
+    }
+
+    fn light() -> DiffuseLight<Color> {
+        DiffuseLight::new(Color::new(5.0, 5.0, 5.0, 1.0))
+    }
+
+    // a diffuse sphere at the origin, lit by a triangle above it
+    fn triangle_lit(light: Arc<dyn Material>) -> World {
+        let mut list = HittableList::default();
+        list.add(Sphere::new(Point3::default(), 1.0, Some(Arc::new(Lambertian::new(gray())))));
+        list.add(Triangle::new(Point3::new(-2.0, 2.0, 0.0), Point3::new(2.0, 2.0, 0.0),
+                               Point3::new(0.0, 2.0, 4.0), Some(light)));
+        World::new(list, HittableList::default(), black, &(0.0 .. 0.0))
+    }
+
+    #[test]
+    fn emissive_triangle() {
+        let world = triangle_lit(Arc::new(light()));
+
+        // light sampling is noisy, unlike the shadow rays to point lights
+        assert_close(&world, &[&PathTracer::new(), &Bdpt::new()], 20000, 0.03);
+    }
+
+    #[test]
+    fn cutout_triangle_light() {
+        let expected = render(&DirectLighting::new(), &triangle_lit(Arc::new(light())), 20000) * 0.5;
+        let world = triangle_lit(Arc::new(Cutout::new(Arc::new(light()), 0.5)));
+
+        for integrator in &[&DirectLighting::new() as &dyn Integrator, &PathTracer::new(), &Bdpt::new()] {
+            let found = render(*integrator, &world, 20000);
+            assert!((&found - &expected).length() <= 0.03 * expected.length(),
+                    "{:?} gives {:?}, half the light is {:?}", integrator, found, expected);
+        }
+    }
+}
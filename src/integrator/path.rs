@@ -0,0 +1,201 @@
+use crate::{
+    hittable::{collection::World, HitRecord, Hittable},
+    integrator::{
+        area_light_color, delta_light_color, material_at, phong_highlight, power_heuristic, sample_material,
+        Integrator,
+    },
+    material::Material,
+    photon_map::PhotonMap,
+    prelude::*,
+};
+
+
+/**
+ * The default integrator, a path tracer which samples the light sources at
+ * every hit and weights the light sample and the material sample by
+ * multiple importance sampling.
+ */
+#[derive(Debug)]
+pub struct PathTracer {
+    depth: usize,
+    russian_roulette_depth: usize,
+    photon_map: Option<PhotonMap>,
+}
+
+impl Default for PathTracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathTracer {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 64,
+            russian_roulette_depth: 3,
+            photon_map: None,
+        }
+    }
+
+    /// Hard limit for the number of bounces of a path
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /**
+     * Paths are terminated randomly by russian roulette once they reached
+     * this depth. Lower values are faster, but add noise.
+     */
+    #[must_use]
+    pub const fn russian_roulette(mut self, min_depth: usize) -> Self {
+        self.russian_roulette_depth = min_depth;
+        self
+    }
+
+    /// Caustics are taken from the photon map instead of being found by paths
+    #[must_use]
+    pub fn photon_map(mut self, photon_map: PhotonMap) -> Self {
+        self.photon_map = Some(photon_map);
+        self
+    }
+
+    /**
+     * Light arriving at the hit directly and, with a photon map, over
+     * specular bounces. It is gathered for all parts of the material which
+     * don't scatter into single directions, whichever part continues the path.
+     */
+    fn direct_light(&self, world: &World, ray: &Ray, hit: &HitRecord, rng: &mut FastRng) -> Vec3 {
+        let material = material_at(world, hit);
+        let mut color = delta_light_color(world, ray, hit, material.as_ref())
+            + area_light_color(world, ray, hit, material.as_ref(), rng);
+
+        if let Some(ref photon_map) = self.photon_map {
+            color += Self::caustic_color(photon_map, ray, hit, material.as_ref());
+        }
+
+        color
+    }
+
+    /**
+     * Weight of light found by a material sample of the given density,
+     * against finding it by the light sample at the same hit.
+     */
+    fn light_weight(world: &World, ray: &Ray, pdf: f64) -> f64 {
+        if pdf > 0.0 {
+            power_heuristic(pdf, world.lights.pdf_value(&ray.origin, &ray.direction.unit()))
+        }
+        else {
+            1.0
+        }
+    }
+
+    /**
+     * Caustics from the photon map. The reflectance for each photon is what
+     * the material scatters from the photon's direction towards the ray.
+     */
+    fn caustic_color(photon_map: &PhotonMap, ray: &Ray, hit: &HitRecord, material: &dyn Material) -> Vec3 {
+        photon_map.radiance(hit, |direction| {
+            let to_light = -direction.unit();
+            let cos = to_light.dot(&hit.normal);
+
+            if cos <= 0.0 {
+                return Vec3::default();
+            }
+
+            let incoming = Ray::new(hit.point.clone(), to_light, ray.departure_time).with_wavelength(ray.wavelength);
+            material.eval(ray, hit, &incoming) / cos.max(0.01)
+        })
+    }
+}
+
+impl Integrator for PathTracer {
+    /**
+     * Traces a path through the scene. Each scattering event multiplies the
+     * throughput of the path, which weights all light found further down the
+     * path. After the minimum depth, paths are terminated randomly with a
+     * probability that rises as the throughput drops, surviving paths are
+     * weighted up to keep the result unbiased (russian roulette).
+     *
+     * With a photon map, caustics are gathered at every hit. Light which
+     * reaches an emitter over specular bounces after a diffuse or glossy
+     * bounce is then skipped if the emitter sent out photons, the photon
+     * map holds it already.
+     */
+    fn ray_color(&self, mut ray: Ray, world: &World, rng: &mut FastRng) -> Vec3 {
+
+        let photon_map = self.photon_map.as_ref();
+
+        let mut color = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+        let mut diffuse_seen = false;
+        let mut caustic = false;
+
+        // density of the material sample which led here, zero for the
+        // camera ray and after specular scattering
+        let mut last_pdf = 0.0;
+
+        // the depth is only a safety limit, paths usually end by russian roulette
+        for bounce in 0 .. self.depth {
+
+            // info!("ray_color bounce={}", bounce);
+
+            let Some(hit) = world.hit(&ray, &(0.0001..f64::INFINITY)) else {
+                // If the ray hits nothing, add the background color.
+                let background: Vec3 = world.background(&ray).into();
+                color += &throughput * background * Self::light_weight(world, &ray, last_pdf);
+                break;
+            };
+
+            let material = material_at(world, &hit);
+
+            if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+                if !(caustic && photon_map.is_some_and(|map| map.holds_caustics_of(&material))) {
+                    color += &throughput * emitted * Self::light_weight(world, &ray, last_pdf);
+                }
+            }
+
+            color += &throughput * self.direct_light(world, &ray, &hit, rng);
+
+            let sample = material
+                .scatter(&ray, &hit)
+                .and_then(|srec| sample_material(&ray, &hit, material.as_ref(), srec, rng));
+            let Some(sample) = sample else {
+                break;
+            };
+
+            let mut light_multi = 1.0;
+
+            if sample.pdf > 0.0 {
+                diffuse_seen = true;
+                caustic = false;
+
+                if world.lights.pdf_value(&sample.ray.origin, &sample.ray.direction) > 0.0 {
+                    light_multi += phong_highlight(&sample.ray.direction, &ray.direction, &hit.normal, &material.settings());
+                }
+            }
+            else {
+                caustic = diffuse_seen;
+            }
+
+            throughput = sample.weight * &throughput * light_multi;
+            last_pdf = sample.pdf;
+            ray = sample.ray;
+
+            if bounce + 1 >= self.russian_roulette_depth {
+                let max = throughput.x.max(throughput.y).max(throughput.z);
+                let survive = max.min(1.0);
+
+                if survive <= 0.0 || rng.gen() >= survive {
+                    break;
+                }
+
+                throughput /= survive;
+            }
+        }
+
+        color
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::{delta_light_color, incoming_light, material_at, sample_material, Integrator},
+    prelude::*,
+};
+
+
+/**
+ * Classic recursive ray tracing after Turner Whitted. Rays are followed
+ * through materials which scatter into single directions, mirrors and
+ * glass. All surfaces are shaded by shadow rays to the lights, diffuse and
+ * glossy ones end the path, there is no indirect light.
+ */
+#[derive(Debug, Clone)]
+pub struct Whitted {
+    depth: usize,
+}
+
+impl Default for Whitted {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Whitted {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 8,
+        }
+    }
+
+    /// Limit for the number of reflections and refractions
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+impl Integrator for Whitted {
+    fn ray_color(&self, mut ray: Ray, world: &World, rng: &mut FastRng) -> Vec3 {
+        let mut color = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+
+        for _ in 0 .. self.depth {
+            let Some(hit) = world.hit(&ray, &(0.0001 .. f64::INFINITY)) else {
+                let background: Vec3 = world.background(&ray).into();
+                return color + throughput * background;
+            };
+
+            let material = material_at(world, &hit);
+            if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+                color += &throughput * emitted;
+            }
+
+            // one shadow ray to each point light, one to a random area light
+            let mut direct = delta_light_color(world, &ray, &hit, material.as_ref());
+
+            if !world.lights.is_empty() {
+                let start = ray.at(hit.t1 - 0.0002);
+                let direction = world.lights.random(&start, rng).unit();
+                let pdf = world.lights.pdf_value(&start, &direction);
+                let light_ray = Ray::new(start, direction, ray.departure_time).with_wavelength(ray.wavelength);
+
+                if pdf > 0.0 {
+                    direct += material.eval(&ray, &hit, &light_ray) * incoming_light(world, &light_ray) / pdf;
+                }
+            }
+
+            color += &throughput * direct;
+
+            // only mirrors and glass are followed, the light from
+            // all other directions is the direct light above
+            let sample = material
+                .scatter(&ray, &hit)
+                .and_then(|srec| sample_material(&ray, &hit, material.as_ref(), srec, rng));
+
+            match sample {
+                Some(sample) if sample.pdf <= 0.0 => {
+                    throughput = sample.weight * &throughput;
+                    ray = sample.ray;
+                },
+                _ => break,
+            }
+        }
+
+        color
+    }
+}
@@ -5,8 +5,11 @@
 
 pub mod camera;
 pub mod hittable;
+pub mod integrator;
+pub mod light;
 pub mod material;
 pub mod painter;
+pub mod photon_map;
 pub mod prelude;
 pub mod texture;
 pub mod sdl_parser;
@@ -0,0 +1,45 @@
+use crate::{
+    light::{Light, LightSample},
+    prelude::*,
+};
+
+
+/// A light at infinite distance, like the sun, shining in one direction
+#[derive(Debug, Clone)]
+pub struct DirectionalLight {
+    // unit vector pointing from the scene towards the light
+    to_light: Vec3,
+    color: Color,
+    multiplier: f64,
+}
+
+impl DirectionalLight {
+    /**
+     * The direction is the direction of travel of the light, e.g. pointing
+     * downwards for a sun in the zenith.
+     */
+    #[must_use]
+    pub fn new(direction: &Vec3, color: Color) -> Self {
+        Self {
+            to_light: -direction.unit(),
+            color,
+            multiplier: 1.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+impl Light for DirectionalLight {
+    fn illuminate(&self, _point: &Point3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: self.to_light.clone(),
+            distance: f64::INFINITY,
+            irradiance: Vec3::from(self.color.clone()) * self.multiplier,
+        })
+    }
+}
@@ -0,0 +1,255 @@
+use {
+    crate::{
+        hittable::{HitRecord, Hittable},
+        prelude::*,
+        texture::HdrImage,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        ops::Range,
+        path::Path,
+    },
+};
+
+
+/**
+ * Piecewise constant distribution over 0 .. 1, which is sampled by
+ * inverting its cumulative distribution function.
+ */
+#[derive(Debug, Clone)]
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn new(func: Vec<f64>) -> Self {
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for (i, f) in func.iter().enumerate() {
+            cdf.push(cdf[i] + f / n);
+        }
+
+        let integral = cdf[func.len()];
+        if integral > 0.0 {
+            for c in &mut cdf {
+                *c /= integral;
+            }
+        }
+        else {
+            // nothing to prefer, sample uniformly
+            for (i, c) in cdf.iter_mut().enumerate() {
+                *c = i as f64 / n;
+            }
+        }
+
+        Self { func, cdf, integral }
+    }
+
+    pub(crate) const fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Density of the piece with the given index
+    pub(crate) fn pdf(&self, index: usize) -> f64 {
+        if self.integral > 0.0 { self.func[index] / self.integral } else { 1.0 }
+    }
+
+    /// Returns the sample in range 0 .. 1, its density and the index of its piece
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn sample(&self, u: f64) -> (f64, f64, usize) {
+        // the last entry of the cdf which is not above u
+        let index = self.cdf.partition_point(|c| *c <= u).clamp(1, self.func.len()) - 1;
+
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let du = if width > 0.0 { (u - self.cdf[index]) / width } else { 0.0 };
+
+        ((index as f64 + du) / self.func.len() as f64, self.pdf(index), index)
+    }
+}
+
+
+/**
+ * Piecewise constant distribution over the unit square, a marginal
+ * distribution picks the row and the row's conditional distribution the
+ * column.
+ */
+#[derive(Debug, Clone)]
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// The function values are given row by row
+    pub(crate) fn new(func: &[f64], width: usize, height: usize) -> Self {
+        let conditional: Vec<Distribution1D> = func
+            .chunks(width)
+            .take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(conditional.iter().map(Distribution1D::integral).collect());
+
+        Self { conditional, marginal }
+    }
+
+    /// Returns the sampled (u, v) point and its density
+    pub(crate) fn sample(&self, rng: &mut FastRng) -> ((f64, f64), f64) {
+        let (v, pdf_v, row) = self.marginal.sample(rng.gen());
+        let (u, pdf_u, _) = self.conditional[row].sample(rng.gen());
+
+        ((u, v), pdf_u * pdf_v)
+    }
+
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub(crate) fn pdf(&self, u: f64, v: f64) -> f64 {
+        let rows = self.conditional.len();
+        let row = ((v * rows as f64) as usize).min(rows - 1);
+        let columns = self.conditional[row].func.len();
+        let column = ((u * columns as f64) as usize).min(columns - 1);
+
+        self.marginal.pdf(row) * self.conditional[row].pdf(column)
+    }
+}
+
+
+/**
+ * Light from all directions, given by an environment map in latitude-longitude
+ * layout: the top row of the image is straight up (+y), the bottom row straight
+ * down and the center of the image looks along -z.
+ *
+ * Directions are importance sampled by the brightness of the map, so small
+ * bright spots like the sun in a photographed sky give little noise. The light
+ * is part of the world's lights and delivers the background for rays which
+ * miss the scene.
+ */
+pub struct EnvironmentLight {
+    image: HdrImage,
+    distribution: Distribution2D,
+    // rotation around the y axis, as fraction of a full turn
+    rotation: f64,
+    intensity: f64,
+}
+
+impl Debug for EnvironmentLight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "EnvironmentLight {{ image: {:?}, rotation: {}, intensity: {} }}",
+            self.image, self.rotation * 360.0, self.intensity
+        ))
+    }
+}
+
+impl EnvironmentLight {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(image: HdrImage) -> Self {
+        let (width, height) = (image.width(), image.height());
+
+        // rows near the poles cover a smaller solid angle
+        let mut func = Vec::with_capacity(width * height);
+        for y in 0 .. height {
+            let sin_theta = (PI * (y as f64 + 0.5) / height as f64).sin();
+            for x in 0 .. width {
+                let p = image.pixel(x, y);
+                func.push(p.dot(&Vec3::new(0.2126, 0.7152, 0.0722)).max(0.0) * sin_theta);
+            }
+        }
+
+        Self {
+            distribution: Distribution2D::new(&func, width, height),
+            image,
+            rotation: 0.0,
+            intensity: 1.0,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// When the file is no Radiance .hdr or OpenEXR file or can't be read
+    pub fn load<P: AsRef<Path>>(p: P) -> Result<Self, String> {
+        Ok(Self::new(HdrImage::load(p)?))
+    }
+
+    /// Turns the environment around the y axis by the given angle in degrees
+    #[must_use]
+    pub fn rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees / 360.0;
+        self
+    }
+
+    #[must_use]
+    pub const fn intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    fn direction_to_uv(&self, direction: &Vec3) -> (f64, f64) {
+        let d = direction.unit();
+        let phi = d.x.atan2(d.z);
+        let u = (phi / (2.0 * PI) - self.rotation).rem_euclid(1.0);
+        let v = d.y.clamp(-1.0, 1.0).acos() / PI;
+        (u, v)
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let phi = 2.0 * PI * (u + self.rotation);
+        let theta = PI * v;
+        Vec3::new(theta.sin() * phi.sin(), theta.cos(), theta.sin() * phi.cos())
+    }
+
+    /// The light arriving from the given direction
+    #[must_use]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn radiance(&self, direction: &Vec3) -> Vec3 {
+        let (u, v) = self.direction_to_uv(direction);
+        let x = (u * self.image.width() as f64) as usize;
+        let y = (v * self.image.height() as f64) as usize;
+        self.image.pixel(x, y) * self.intensity
+    }
+
+    /// Draws a direction with a density proportional to the brightness of the map
+    pub fn sample(&self, rng: &mut FastRng) -> Vec3 {
+        let ((u, v), _) = self.distribution.sample(rng);
+        self.uv_to_direction(u, v)
+    }
+
+    /// Density of `sample()` with respect to the solid angle
+    #[must_use]
+    pub fn pdf(&self, direction: &Vec3) -> f64 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (PI * v).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        // the map covers 2 pi by pi radians
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+// The environment is never hit, it is only there for light sampling
+impl Hittable for EnvironmentLight {
+    fn hit(&self, _ray: &Ray, _unit_limit: &Range<f64>) -> Option<HitRecord> {
+        None
+    }
+
+    fn contains(&self, _point: &Vec3) -> bool {
+        false
+    }
+
+    fn bbox(&self, _time_limit: &Range<f64>) -> Option<AABB> {
+        None
+    }
+
+    fn random(&self, _origin: &Point3, rng: &mut FastRng) -> Vec3 {
+        self.sample(rng)
+    }
+
+    fn pdf_value(&self, _origin: &Point3, direction: &Vec3) -> f64 {
+        self.pdf(direction)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::prelude::*;
+
+pub(crate) mod directional;
+pub(crate) mod environment;
+pub(crate) mod point;
+pub(crate) mod sky;
+pub(crate) mod spot;
+
+pub use {
+    directional::DirectionalLight,
+    environment::EnvironmentLight,
+    point::PointLight,
+    sky::PhysicalSky,
+    spot::SpotLight,
+};
+
+
+/// Illumination which a light source delivers to a point in the scene
+#[derive(Debug)]
+pub struct LightSample {
+    // unit vector from the illuminated point towards the light
+    pub direction: Vec3,
+    // distance to the light, infinite for lights at infinity
+    pub distance: f64,
+    // irradiance arriving at a surface facing the light
+    pub irradiance: Vec3,
+}
+
+
+/**
+ * Light sources which have no surface and thus can't be hit by rays,
+ * like point, directional and spot lights. They are sampled explicitly
+ * with a shadow ray from every diffuse hit.
+ */
+pub trait Light: Send + Sync {
+    fn illuminate(&self, point: &Point3) -> Option<LightSample>;
+}
+
+
+/**
+ * Attenuation of a light over distance. Without fade parameters this is
+ * the physical inverse square law. With fade parameters it is the POV-Ray
+ * style fading, where the light has its full intensity at the fade distance
+ * and falls off with the given power. A power of zero means no fading at all.
+ */
+pub(crate) fn attenuation(distance: f64, fade: Option<(f64, f64)>) -> f64 {
+    match fade {
+        None => 1.0 / (distance * distance),
+        Some((fade_distance, fade_power)) => {
+            if fade_power <= 0.0 || fade_distance <= 0.0 {
+                1.0
+            }
+            else {
+                2.0 / (1.0 + (distance / fade_distance).powf(fade_power))
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    light::{attenuation, Light, LightSample},
+    prelude::*,
+};
+
+
+/// A light which shines equally in all directions from a single point
+#[derive(Debug, Clone)]
+pub struct PointLight {
+    position: Point3,
+    color: Color,
+    multiplier: f64,
+    fade: Option<(f64, f64)>,
+}
+
+impl PointLight {
+    #[must_use]
+    pub const fn new(position: Point3, color: Color) -> Self {
+        Self {
+            position,
+            color,
+            multiplier: 1.0,
+            fade: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /**
+     * Replaces the inverse square falloff by POV-Ray style fading,
+     * see `attenuation()` for details.
+     */
+    #[must_use]
+    pub const fn fade(mut self, distance: f64, power: f64) -> Self {
+        self.fade = Some((distance, power));
+        self
+    }
+}
+
+impl Light for PointLight {
+    fn illuminate(&self, point: &Point3) -> Option<LightSample> {
+        let to_light = &self.position - point;
+        let distance = to_light.length();
+
+        if distance <= 0.0 {
+            return None;
+        }
+
+        let intensity = self.multiplier * attenuation(distance, self.fade);
+
+        Some(LightSample {
+            direction: to_light / distance,
+            distance,
+            irradiance: Vec3::from(self.color.clone()) * intensity,
+        })
+    }
+}
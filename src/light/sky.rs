@@ -0,0 +1,219 @@
+use crate::{
+    light::DirectionalLight,
+    prelude::*,
+};
+
+
+// Perez distribution coefficients, linear in the turbidity
+#[derive(Debug, Clone, Default)]
+struct Perez {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+}
+
+impl Perez {
+    fn new(t: f64, coefficients: [[f64; 2]; 5]) -> Self {
+        let [a, b, c, d, e] = coefficients.map(|[m, n]| m.mul_add(t, n));
+        Self { a, b, c, d, e }
+    }
+
+    // relative luminance at zenith angle theta and angle gamma to the sun
+    #[allow(clippy::suboptimal_flops)] // the formula as in the paper
+    fn f(&self, cos_theta: f64, gamma: f64) -> f64 {
+        (1.0 + self.a * (self.b / cos_theta.max(0.01)).exp())
+            * (1.0 + self.c * (self.d * gamma).exp() + self.e * gamma.cos().powi(2))
+    }
+}
+
+
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Vec3 {
+    let rgb = xyz_to_rgb(&Vec3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance));
+
+    Vec3::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+
+// illuminance units per kcd/m², a clear sky sun of 100 klux gives an irradiance of pi
+const LUMINANCE_SCALE: f64 = PI / 100.0;
+
+
+/**
+ * The analytic daylight model of Preetham, Shirley and Smits. The sky color
+ * depends on the direction to the sun and the turbidity of the air, which is
+ * about 2 for a very clear sky and 10 for a hazy one. Below the horizon the
+ * sky shows a diffuse ground, which is lit by the sun and the sky.
+ *
+ * The sky itself contains no sun disc, the sun is a separate directional
+ * light with matching color, see `sun()`. Both are set up at once by
+ * `World::set_sky()`.
+ */
+#[derive(Debug, Clone)]
+pub struct PhysicalSky {
+    // unit vector towards the sun
+    sun_direction: Vec3,
+    turbidity: f64,
+    ground_albedo: f64,
+    intensity: f64,
+
+    perez_y: Perez,
+    perez_x: Perez,
+    perez_yy: Perez,
+    // zenith values divided by the Perez function at the zenith
+    zenith: (f64, f64, f64),
+    ground: Vec3,
+}
+
+impl PhysicalSky {
+    /// The direction points from the scene towards the sun
+    #[must_use]
+    pub fn new(sun_direction: &Vec3) -> Self {
+        Self {
+            sun_direction: sun_direction.unit(),
+            turbidity: 3.0,
+            ground_albedo: 0.3,
+            intensity: 1.0,
+            perez_y: Perez::default(),
+            perez_x: Perez::default(),
+            perez_yy: Perez::default(),
+            zenith: (0.0, 0.0, 0.0),
+            ground: Vec3::default(),
+        }.update()
+    }
+
+    /// Haziness of the air, sensible values are 2 .. 10
+    #[must_use]
+    pub fn turbidity(mut self, turbidity: f64) -> Self {
+        self.turbidity = turbidity.clamp(1.7, 10.0);
+        self.update()
+    }
+
+    /// Reflectance of the ground below the horizon
+    #[must_use]
+    pub fn ground_albedo(mut self, albedo: f64) -> Self {
+        self.ground_albedo = albedo.clamp(0.0, 1.0);
+        self.update()
+    }
+
+    /// Scales sky and sun
+    #[must_use]
+    pub fn intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self.update()
+    }
+
+    // the model is only valid for a sun above the horizon
+    fn theta_sun(&self) -> f64 {
+        self.sun_direction.y.clamp(0.0, 1.0).acos().min(PI / 2.0 - 0.001)
+    }
+
+    #[allow(clippy::suboptimal_flops)] // the fits as in the paper
+    fn update(mut self) -> Self {
+        let t = self.turbidity;
+        let theta_s = self.theta_sun();
+
+        self.perez_y = Perez::new(t, [[0.1787, -1.4630], [-0.3554, 0.4275], [-0.0227, 5.3251],
+                                      [0.1206, -2.5771], [-0.0670, 0.3703]]);
+        self.perez_x = Perez::new(t, [[-0.0193, -0.2592], [-0.0665, 0.0008], [-0.0004, 0.2125],
+                                      [-0.0641, -0.8989], [-0.0033, 0.0452]]);
+        self.perez_yy = Perez::new(t, [[-0.0167, -0.2608], [-0.0950, 0.0092], [-0.0079, 0.2102],
+                                       [-0.0441, -1.6537], [-0.0109, 0.0529]]);
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+        let poly = |c: [f64; 4]| c[0] * theta_s.powi(3) + c[1] * theta_s.powi(2) + c[2] * theta_s + c[3];
+        let x = t * t * poly([0.00166, -0.00375, 0.00209, 0.0])
+            + t * poly([-0.02903, 0.06377, -0.03202, 0.00394])
+            + poly([0.11693, -0.21196, 0.06052, 0.25886]);
+        let y = t * t * poly([0.00275, -0.00610, 0.00317, 0.0])
+            + t * poly([-0.04214, 0.08970, -0.04153, 0.00516])
+            + poly([0.15346, -0.26756, 0.06670, 0.26688]);
+
+        let cos_s = theta_s.cos();
+        self.zenith = (
+            luminance.max(0.0) / self.perez_y.f(1.0, theta_s),
+            x / self.perez_x.f(1.0, theta_s),
+            y / self.perez_yy.f(1.0, theta_s));
+
+        // the ground is a lambertian surface, lit by the sun and the upper hemisphere
+        let mut sky_irradiance = Vec3::default();
+        let steps = 32;
+        for i in 0 .. steps {
+            let theta = (f64::from(i) + 0.5) / f64::from(steps) * PI / 2.0;
+            for j in 0 .. 2 * steps {
+                let phi = (f64::from(j) + 0.5) / f64::from(steps) * PI;
+                let direction = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                let solid_angle = theta.sin() * (PI / 2.0 / f64::from(steps)) * (PI / f64::from(steps));
+                sky_irradiance += self.sky_radiance(&direction) * (theta.cos() * solid_angle);
+            }
+        }
+        let sun_irradiance = self.sun_color() * (PI * cos_s);
+
+        self.ground = (sky_irradiance + sun_irradiance) * (self.ground_albedo / PI);
+        self
+    }
+
+    fn sky_radiance(&self, direction: &Vec3) -> Vec3 {
+        let cos_theta = direction.y.max(0.0);
+        let gamma = clamp(direction.dot(&self.sun_direction), -1.0 ..= 1.0).acos();
+
+        let luminance = self.zenith.0 * self.perez_y.f(cos_theta, gamma);
+        let x = self.zenith.1 * self.perez_x.f(cos_theta, gamma);
+        let y = self.zenith.2 * self.perez_yy.f(cos_theta, gamma);
+
+        xyy_to_rgb(x, y, luminance) * (LUMINANCE_SCALE * self.intensity)
+    }
+
+    /**
+     * Color of the sunlight after passing the atmosphere, by the Rayleigh
+     * and aerosol extinction of the model, for the RGB wavelengths.
+     */
+    #[allow(clippy::suboptimal_flops)] // the fits as in the paper
+    fn sun_color(&self) -> Vec3 {
+        if self.sun_direction.y <= 0.0 {
+            return Vec3::default();
+        }
+
+        let theta_s = self.theta_sun();
+        let optical_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - theta_s.to_degrees()).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+
+        let transmittance = |lambda: f64| {
+            let rayleigh = (-0.008_735 * lambda.powf(-4.08) * optical_mass).exp();
+            let aerosol = (-beta * lambda.powf(-1.3) * optical_mass).exp();
+            rayleigh * aerosol
+        };
+
+        // wavelengths in micrometers
+        Vec3::new(transmittance(0.65), transmittance(0.57), transmittance(0.475)) * self.intensity
+    }
+
+    /// Light arriving from the given direction
+    #[must_use]
+    pub fn radiance(&self, direction: &Vec3) -> Vec3 {
+        if direction.y < 0.0 {
+            self.ground.clone()
+        }
+        else {
+            self.sky_radiance(direction)
+        }
+    }
+
+    /// The sky as background function of a `World`
+    #[must_use]
+    pub fn color(&self, ray: &Ray) -> Color {
+        let radiance = self.radiance(&ray.direction.unit());
+        Color::new64(radiance.x, radiance.y, radiance.z, 1.0)
+    }
+
+    /// The sun which belongs to this sky
+    #[must_use]
+    pub fn sun(&self) -> DirectionalLight {
+        let color = self.sun_color();
+        DirectionalLight::new(&-&self.sun_direction, Color::new64(color.x, color.y, color.z, 1.0))
+            .multiplier(PI)
+    }
+}
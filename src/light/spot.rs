@@ -0,0 +1,117 @@
+use crate::{
+    light::{attenuation, Light, LightSample},
+    prelude::*,
+};
+
+
+/**
+ * A point light which only shines into a cone. Inside of the radius angle
+ * the light has full intensity, it falls off smoothly towards the falloff
+ * angle and is dark outside of it. Angles are given in degrees, measured
+ * from the axis of the cone.
+ */
+#[derive(Debug, Clone)]
+pub struct SpotLight {
+    position: Point3,
+    axis: Vec3,
+    color: Color,
+    multiplier: f64,
+    cos_radius: f64,
+    cos_falloff: f64,
+    tightness: f64,
+    fade: Option<(f64, f64)>,
+}
+
+impl SpotLight {
+    #[must_use]
+    pub fn new(position: Point3, point_at: &Point3, color: Color) -> Self {
+        let axis = (point_at - &position).unit();
+
+        Self {
+            position,
+            axis,
+            color,
+            multiplier: 1.0,
+            cos_radius: 30.0_f64.to_radians().cos(),
+            cos_falloff: 45.0_f64.to_radians().cos(),
+            tightness: 0.0,
+            fade: None,
+        }
+    }
+
+    #[must_use]
+    pub fn radius(mut self, degrees: f64) -> Self {
+        self.cos_radius = degrees.to_radians().cos();
+        self
+    }
+
+    #[must_use]
+    pub fn falloff(mut self, degrees: f64) -> Self {
+        self.cos_falloff = degrees.to_radians().cos();
+        self
+    }
+
+    #[must_use]
+    pub const fn tightness(mut self, tightness: f64) -> Self {
+        self.tightness = tightness;
+        self
+    }
+
+    #[must_use]
+    pub const fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /**
+     * Replaces the inverse square falloff by POV-Ray style fading,
+     * see `attenuation()` for details.
+     */
+    #[must_use]
+    pub const fn fade(mut self, distance: f64, power: f64) -> Self {
+        self.fade = Some((distance, power));
+        self
+    }
+
+    fn cone_factor(&self, cos_angle: f64) -> f64 {
+        if cos_angle <= self.cos_falloff {
+            return 0.0;
+        }
+
+        let tight = if self.tightness > 0.0 { cos_angle.powf(self.tightness) } else { 1.0 };
+
+        if cos_angle >= self.cos_radius {
+            return tight;
+        }
+
+        // smooth hermite step between falloff and radius
+        let t = (cos_angle - self.cos_falloff) / (self.cos_radius - self.cos_falloff);
+        tight * t * t * 2.0f64.mul_add(-t, 3.0)
+    }
+}
+
+impl Light for SpotLight {
+    fn illuminate(&self, point: &Point3) -> Option<LightSample> {
+        let to_light = &self.position - point;
+        let distance = to_light.length();
+
+        if distance <= 0.0 {
+            return None;
+        }
+
+        let direction = to_light / distance;
+        let cone = self.cone_factor(-direction.dot(&self.axis));
+
+        if cone <= 0.0 {
+            return None;
+        }
+
+        let intensity = self.multiplier * cone * attenuation(distance, self.fade);
+
+        Some(LightSample {
+            direction,
+            distance,
+            irradiance: Vec3::from(self.color.clone()) * intensity,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Material, ScatterRecord},
+    prelude::*,
+    texture::Texture,
+};
+use crate::material::CommonMaterialSettings;
+
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+
+// separates the holes from the choices of the wrapped material
+const SALT: u64 = 0x6375_746f_7574_5f68;
+
+
+/**
+ * Cuts holes into the surfaces of the wrapped material, e.g. for leaves
+ * or fences. The red channel of the opacity texture is the probability
+ * that a ray is blocked, with a threshold rays are blocked where the
+ * opacity reaches it. Rays pass the holes as if there was no surface,
+ * shadow rays included.
+ */
+pub struct Cutout {
+    material: Arc<dyn Material>,
+    opacity: Arc<dyn Texture>,
+    threshold: Option<f64>,
+}
+
+impl Debug for Cutout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Cutout {{ threshold: {:?} }}", self.threshold
+        ))
+    }
+}
+
+impl Cutout {
+    #[must_use]
+    pub fn new<T: Texture + 'static>(material: Arc<dyn Material>, opacity: T) -> Self {
+        Self {
+            material,
+            opacity: Arc::new(opacity),
+            threshold: None,
+        }
+    }
+
+    /// Hard edged holes instead of a stochastic transparency
+    #[must_use]
+    pub const fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        self.material.scatter(ray, hit)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        self.material.eval(ray_in, hit, ray_out)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        self.material.pdf(ray_in, hit, ray_out)
+    }
+
+    fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
+        self.material.emitted(u, v, point)
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.emissive()
+    }
+
+    fn opaque(&self, ray: &Ray, hit: &HitRecord) -> bool {
+        let opacity = clamp(f64::from(self.opacity.color(hit.u, hit.v, &hit.point).r), 0.0 ..= 1.0);
+
+        // without a threshold, the same ray always makes the same choice at the same hit
+        let opaque = self.threshold.map_or_else(
+            || opacity >= 1.0 || FastRng::for_hit_salted(ray, hit.t1, SALT).gen() < opacity,
+            |threshold| opacity >= threshold,
+        );
+
+        opaque && self.material.opaque(ray, hit)
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.material.settings()
+    }
+}
@@ -24,11 +24,110 @@ impl ReflectProbabilityCurve for Glass {
     }
 }
 
+/**
+ * Wavelength dependent refractive index. The coefficients are given for
+ * wavelengths in micrometers, as is common in optical glass catalogs.
+ */
+#[derive(Debug, Clone)]
+pub enum Dispersion {
+    // n = a + b / λ²
+    Cauchy { a: f64, b: f64 },
+    // n² = 1 + Σ b_i λ² / (λ² - c_i)
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    // Schott N-BK7 crown glass
+    pub const BK7: Self = Self::Sellmeier {
+        b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+        c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+    };
+
+    // Schott SF11 dense flint glass
+    pub const SF11: Self = Self::Sellmeier {
+        b: [1.737_596_95, 0.313_747_346, 1.898_781_01],
+        c: [0.013_188_707, 0.062_306_814_2, 155.236_29],
+    };
+
+    // diamond
+    pub const DIAMOND: Self = Self::Cauchy { a: 2.3753, b: 0.012_1 };
+
+    /**
+     * A Cauchy curve through the given refractive index at 550nm, where the
+     * index at 380nm is `ratio` times the index at 780nm. This is how POV-Ray
+     * specifies dispersion.
+     */
+    #[must_use]
+    pub fn from_ratio(refractive: f64, ratio: f64) -> Self {
+        if (ratio - 1.0).abs() < 1e-9 {
+            return Self::Cauchy { a: refractive, b: 0.0 };
+        }
+
+        let x_violet = 1.0 / 0.380_f64.powi(2);
+        let x_red = 1.0 / 0.780_f64.powi(2);
+        let x_mid = 1.0 / 0.550_f64.powi(2);
+
+        // a = k * b follows from n(violet) = ratio * n(red)
+        let k = ratio.mul_add(x_red, -x_violet) / (1.0 - ratio);
+        let b = refractive / (k + x_mid);
+
+        Self::Cauchy { a: k * b, b }
+    }
+
+    /// Refractive index for a wavelength in nanometers
+    #[must_use]
+    pub fn refractive(&self, wavelength: f64) -> f64 {
+        let l2 = (wavelength * 0.001).powi(2);
+
+        match self {
+            Self::Cauchy { a, b } => a + b / l2,
+            Self::Sellmeier { b, c } => {
+                let n2 = 1.0 + (0 .. 3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>();
+                n2.sqrt()
+            }
+        }
+    }
+}
+
+
+// How light is absorbed on its way through the inside of a dielectric
+#[derive(Debug, Clone)]
+enum Absorption {
+    // Beer-Lambert law, the coefficients are per unit of distance
+    BeerLambert(Vec3),
+    // PovRay interior fading, light reaches the fade color over distance
+    Fade { distance: f64, power: f64, color: Vec3 },
+}
+
+impl Absorption {
+    fn transmittance(&self, distance: f64) -> Vec3 {
+        match self {
+            Self::BeerLambert(sigma) => {
+                Vec3::new((-sigma.x * distance).exp(), (-sigma.y * distance).exp(), (-sigma.z * distance).exp())
+            },
+            Self::Fade { distance: fade_distance, power, color } => {
+                // PovRay uses exponential fading for powers of 1000 and above
+                let a = if *power >= 1000.0 {
+                    (-distance / fade_distance).exp()
+                }
+                else {
+                    1.0 / (1.0 + (distance / fade_distance).powf(*power))
+                };
+
+                color + (Vec3::new(1.0, 1.0, 1.0) - color) * a
+            }
+        }
+    }
+}
+
+
 #[derive(Debug)]
 pub struct Dielectric {
     color: Color,
     enter_refractive: f64,
     outer_refractive: f64,
+    dispersion: Option<Dispersion>,
+    absorption: Option<Absorption>,
     reflect_curve: Option<Box<dyn ReflectProbabilityCurve>>,
     settings: CommonMaterialSettings,
 }
@@ -42,6 +141,8 @@ impl Dielectric {
             color,
             enter_refractive,
             outer_refractive,
+            dispersion: None,
+            absorption: None,
             reflect_curve: None,
             settings: CommonMaterialSettings::new(),
         }
@@ -52,15 +153,56 @@ impl Dielectric {
         self
     }
 
+    /**
+     * With a dispersion curve the refractive index depends on the wavelength
+     * of the ray. Dispersion is only visible in spectral rendering mode, in
+     * RGB mode the refractive index given to `new()` is used.
+     */
+    #[must_use]
+    pub const fn dispersion(mut self, dispersion: Dispersion) -> Self {
+        self.dispersion = Some(dispersion);
+        self
+    }
+
+    /**
+     * Light traveling through the object is absorbed following the
+     * Beer-Lambert law, with separate coefficients per color channel.
+     * Thick parts of an object are darker than thin parts.
+     */
+    #[must_use]
+    pub const fn absorption(mut self, coefficients: Vec3) -> Self {
+        self.absorption = Some(Absorption::BeerLambert(coefficients));
+        self
+    }
+
+    /**
+     * POV-Ray style absorption: light fades towards the color with growing
+     * distance, about halfway at the fade distance. Powers of 1000 and above
+     * give an exponential fading.
+     */
+    #[must_use]
+    pub fn fade(mut self, distance: f64, power: f64, color: Color) -> Self {
+        self.absorption = Some(Absorption::Fade { distance, power, color: color.into() });
+        self
+    }
+
     fn refract(&self, ray: &Ray, hit: &HitRecord) -> Option<Ray> {
         assert!((ray.direction.length_squared() - 1.0).abs() < 0.00001);
 
         let cos_theta = (-&ray.direction).dot(&hit.normal);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-        let refractive = if hit.outside {
-            self.enter_refractive
-        } else {
-            self.outer_refractive
+        let refractive = match (&self.dispersion, ray.wavelength) {
+            (Some(dispersion), Some(wavelength)) => {
+                let n = dispersion.refractive(wavelength);
+                if hit.outside { 1.0 / n } else { n }
+            },
+            _ => {
+                if hit.outside {
+                    self.enter_refractive
+                } else {
+                    self.outer_refractive
+                }
+            }
         };
         if refractive * sin_theta > 1.0 {
             return None;
@@ -69,7 +211,7 @@ impl Dielectric {
             .reflect_curve
             .as_ref()
             .map_or(0.0, |r| r.reflect_prob(cos_theta, refractive));
-        if Random::normal() < reflect_prob {
+        if FastRng::for_hit(ray, hit.t1).gen() < reflect_prob {
             return None;
         }
         let r_parallel = refractive * (&ray.direction + cos_theta * &hit.normal);
@@ -84,14 +226,33 @@ impl Material for Dielectric {
         let refract = self
             .refract(ray, &hit)
             .unwrap_or_else(|| reflect(ray, &hit));
+
+        // a ray which hits the inside of the surface has traveled through the object
+        let color = match &self.absorption {
+            Some(absorption) if !hit.outside => {
+                let t = absorption.transmittance(hit.t1);
+                Color::new64(f64::from(self.color.r) * t.x, f64::from(self.color.g) * t.y, f64::from(self.color.b) * t.z, 1.0)
+            },
+            _ => self.color.clone(),
+        };
+
         Some(ScatterRecord {
-            color: self.color.clone(),
+            color,
             ray: Some(refract),
             pdf: Box::new(CosinePdf::new(&hit.normal)), 
             skip_pdf: true,            
         })
     }
 
+    // reflection and refraction both go into single directions
+    fn eval(&self, _ray_in: &Ray, _hit: &HitRecord, _ray_out: &Ray) -> Vec3 {
+        Vec3::default()
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _hit: &HitRecord, _ray_out: &Ray) -> f64 {
+        0.0
+    }
+
     fn settings(&self) -> CommonMaterialSettings {
         self.settings.clone()
     }
@@ -3,11 +3,13 @@ use crate::{
     prelude::*,
 };
 use crate::material::CommonMaterialSettings;
+use crate::material::PhaseFunction;
 
 
 #[derive(Debug, Clone)]
 pub struct Isotropic {
     color: Color,
+    phase: PhaseFunction,
     settings: CommonMaterialSettings,
 }
 
@@ -16,18 +18,26 @@ impl Isotropic {
     pub fn new(color: Color) -> Self {
         Self {
             color,
+            phase: PhaseFunction::Isotropic,
             settings: CommonMaterialSettings::new(),
         }
     }
+
+    /// Despite the name, media can scatter light unevenly
+    #[must_use]
+    pub const fn phase(mut self, phase: PhaseFunction) -> Self {
+        self.phase = phase;
+        self
+    }
 }
 
 impl Material for Isotropic {
 
-    fn scatter(&self, _ray: &Ray, _hit: &HitRecord) -> Option<ScatterRecord> {
+    fn scatter(&self, ray: &Ray, _hit: &HitRecord) -> Option<ScatterRecord> {
         Some(ScatterRecord {
             color: self.color.clone(),
             ray: None,
-            pdf: Box::new(SpherePdf::new()), 
+            pdf: self.phase.pdf(&ray.direction),
             skip_pdf: false,            
         })
     }
@@ -1,23 +1,50 @@
 use crate::{
-    material::Material,
+    hittable::HitRecord,
+    material::{Material, ScatterRecord},
     prelude::*,
     texture::Texture,
 };
 use crate::material::CommonMaterialSettings;
 
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+
+/**
+ * A surface which emits light, the texture gives the emitted color at
+ * each point. The emitted radiance is the texture color times the tint
+ * times the multiplier. The renderer works with linear radiance, a value
+ * of 1.0 is displayed as full white and is taken as 1 W/(sr m²).
+ *
+ * By default the light reflects nothing, with a surface material light
+ * from elsewhere is scattered by that material on top of the emission.
+ */
+#[derive(Clone)]
 pub struct DiffuseLight<T> {
     texture: T,
+    tint: Color,
     multiplier: f64,
+    surface: Option<Arc<dyn Material>>,
     settings: CommonMaterialSettings,
 }
 
+impl<T: Debug> Debug for DiffuseLight<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "DiffuseLight {{ texture: {:?}, tint: {:?}, multiplier: {}, surface: {} }}",
+            self.texture, self.tint, self.multiplier, self.surface.is_some()
+        ))
+    }
+}
+
 impl<T> DiffuseLight<T> {
     pub fn new(texture: T) -> Self {
         Self {
             texture,
+            tint: Color::new(1.0, 1.0, 1.0, 1.0),
             multiplier: 1.0,
+            surface: None,
             settings: CommonMaterialSettings::new(),
         }
     }
@@ -26,15 +53,68 @@ impl<T> DiffuseLight<T> {
         self.multiplier = multiplier;
         self
     }
+
+    /// Colors the emission of textures, like POV-Ray's emission color does
+    #[must_use]
+    pub const fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /**
+     * The radiance of a white texture in nits, luminous efficacy is
+     * ignored, so one nit is one unit of radiance.
+     */
+    #[must_use]
+    pub const fn nits(self, nits: f64) -> Self {
+        self.multiplier(nits)
+    }
+
+    /**
+     * The total power in watts of a white texture, which is spread
+     * evenly over the given area and emitted into the hemisphere.
+     */
+    #[must_use]
+    pub fn watts(self, watts: f64, area: f64) -> Self {
+        self.multiplier(watts / (PI * area))
+    }
+
+    /// The material which scatters the light falling onto the emitter
+    #[must_use]
+    pub fn surface(mut self, surface: Arc<dyn Material>) -> Self {
+        self.surface = Some(surface);
+        self
+    }
 }
 
 impl<T: Texture> Material for DiffuseLight<T> {
 
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        self.surface.as_ref()?.scatter(ray, hit)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        self.surface.as_ref().map_or_else(Vec3::default, |surface| surface.eval(ray_in, hit, ray_out))
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        self.surface.as_ref().map_or(0.0, |surface| surface.pdf(ray_in, hit, ray_out))
+    }
+
     fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
-        Some(<Color as Into<Vec3>>::into(self.texture.color(u, v, point)) * self.multiplier)
+        let color = self.texture.color(u, v, point) * self.tint.clone();
+        Some(<Color as Into<Vec3>>::into(color) * self.multiplier)
+    }
+
+    fn emissive(&self) -> bool {
+        true
+    }
+
+    fn opaque(&self, ray: &Ray, hit: &HitRecord) -> bool {
+        self.surface.as_ref().is_none_or(|surface| surface.opaque(ray, hit))
     }
 
     fn settings(&self) -> CommonMaterialSettings {
-        self.settings.clone()
+        self.surface.as_ref().map_or_else(|| self.settings.clone(), |surface| surface.settings())
     }
 }
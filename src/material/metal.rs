@@ -58,7 +58,7 @@ impl Material for DiffuseMetal {
         if reflected.direction.dot(&hit.normal) > 0.0 {
             Some(ScatterRecord {
                 color,
-                ray: Some(reflected),
+                ray: None,
                 pdf: Box::new(ReflectionPdf::new(ray.direction.clone(), hit.normal.clone(), self.exponent)),
                 skip_pdf: false,            
             })
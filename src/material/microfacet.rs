@@ -0,0 +1,439 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Material, ScatterRecord},
+    prelude::*,
+    texture::Texture,
+};
+use crate::material::CommonMaterialSettings;
+
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+
+/**
+ * Fresnel reflectance of an interface between two dielectrics for
+ * unpolarized light. Eta is the ratio of the refractive index behind the
+ * interface to the index in front of it.
+ */
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+
+    if sin2_t >= 1.0 {
+        // total internal reflection
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let rs = eta.mul_add(-cos_t, cos_i) / eta.mul_add(cos_t, cos_i);
+    let rp = eta.mul_add(cos_i, -cos_t) / eta.mul_add(cos_i, cos_t);
+
+    (rs * rs + rp * rp) * 0.5
+}
+
+
+/**
+ * Fresnel reflectance of a conductor with the complex refractive
+ * index eta + i*k, for unpolarized light.
+ */
+pub fn fresnel_conductor(cos_i: f64, eta: f64, k: f64) -> f64 {
+    let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+
+    let t0 = eta.mul_add(eta, -(k * k)) - sin2;
+    let a2b2 = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+    let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+
+    let t1 = a2b2 + cos2;
+    let t2 = 2.0 * cos_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    #[allow(clippy::suspicious_operation_groupings)]
+    let t3 = cos2 * a2b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    (rs + rp) * 0.5
+}
+
+
+pub fn fresnel_schlick(cos_i: f64, f0: &Vec3) -> Vec3 {
+    let f = (1.0 - cos_i.clamp(0.0, 1.0)).powi(5);
+    Vec3::new(
+        (1.0 - f0.x).mul_add(f, f0.x),
+        (1.0 - f0.y).mul_add(f, f0.y),
+        (1.0 - f0.z).mul_add(f, f0.z))
+}
+
+
+/**
+ * Reflects the ray at a GGX microfacet normal which is sampled from the
+ * normals visible from the ray. The fresnel function gives the reflectance
+ * for the cosine between the ray and the microfacet normal.
+ */
+pub fn ggx_reflect<F>(ray: &Ray, hit: &HitRecord, roughness: f64, fresnel: F,
+                             rng: &mut FastRng) -> Option<ScatterRecord>
+    where F: Fn(f64) -> Vec3
+{
+    let pdf = GgxPdf::new(&hit.normal, &-&ray.direction, roughness);
+    ggx_reflect_with(pdf, ray, hit, fresnel, rng)
+}
+
+
+/**
+ * Like `ggx_reflect()`, but with a prepared, e.g. anisotropic, distribution.
+ */
+pub fn ggx_reflect_with<F>(pdf: GgxPdf, ray: &Ray, hit: &HitRecord, fresnel: F,
+                           rng: &mut FastRng) -> Option<ScatterRecord>
+    where F: Fn(f64) -> Vec3
+{
+    let wo = pdf.wo().clone();
+    let m = pdf.sample_normal(rng);
+    let wi = 2.0 * wo.dot(&m) * &m - &wo;
+
+    if wi.z <= 0.0 || wo.z <= 0.0 {
+        // reflected into the surface, lost by multiple scattering
+        return None;
+    }
+
+    // sampling the visible normals leaves the fresnel term and
+    // the shadowing of the incoming light as path weight
+    let weight = fresnel(wo.dot(&m)) * (pdf.g2(&wo, &wi) / pdf.g1(&wo));
+
+    let direction = pdf.to_world(&wi);
+
+    Some(ScatterRecord {
+        color: Color::new64(weight.x, weight.y, weight.z, 1.0),
+        ray: Some(Ray::new(hit.point.clone(), direction, ray.departure_time)),
+        pdf: Box::new(pdf),
+        skip_pdf: false,
+    })
+}
+
+
+/**
+ * Scattering function times cosine of a GGX reflection into the given
+ * direction, the counterpart of `ggx_reflect_with()` for `Material::eval()`.
+ */
+pub fn ggx_reflect_eval<F>(pdf: &GgxPdf, direction: &Vec3, fresnel: F) -> Vec3
+    where F: Fn(f64) -> Vec3
+{
+    let wo = pdf.wo();
+    let wi = pdf.to_local(&direction.unit());
+
+    if wi.z <= 0.0 || wo.z <= 0.0 {
+        return Vec3::default();
+    }
+
+    let m = (wo + &wi).unit();
+    let cos_om = wo.dot(&m);
+    if cos_om <= 0.0 {
+        return Vec3::default();
+    }
+
+    fresnel(cos_om) * (pdf.distribution(&m) * pdf.g2(wo, &wi) / (4.0 * wo.z))
+}
+
+
+/**
+ * Reflects or refracts the ray at a sampled GGX microfacet normal of a
+ * dielectric, the choice is made by the Fresnel reflectance.
+ */
+#[allow(clippy::too_many_arguments)] // internal
+pub fn ggx_transmit(ray: &Ray, hit: &HitRecord, color: Color, refractive: f64, roughness: f64,
+                    rng: &mut FastRng) -> Option<ScatterRecord> {
+
+    let pdf = GgxPdf::new(&hit.normal, &-&ray.direction, roughness);
+    let wo = pdf.wo().clone();
+    let m = pdf.sample_normal(rng);
+
+    let eta = if hit.outside { refractive } else { 1.0 / refractive };
+    let cos_o = wo.dot(&m);
+    let fresnel = fresnel_dielectric(cos_o, eta);
+
+    let (wi, scattered_out) = if rng.gen() < fresnel {
+        (2.0 * cos_o * &m - &wo, true)
+    }
+    else {
+        let sin2_t = cos_o.mul_add(-cos_o, 1.0) / (eta * eta);
+        let cos_t = (1.0 - sin2_t).max(0.0).sqrt();
+        ((cos_o / eta - cos_t) * &m - &wo / eta, false)
+    };
+
+    // rays must leave on the side they were meant to
+    if (wi.z > 0.0) != scattered_out || wo.z <= 0.0 {
+        return None;
+    }
+
+    // the fresnel term is cancelled by the choice between reflection
+    // and refraction, only the shadowing of the incoming light remains
+    let weight = pdf.g2(&wo, &wi) / pdf.g1(&wo);
+
+    let direction = pdf.to_world(&wi).unit();
+
+    Some(ScatterRecord {
+        color: color * weight,
+        ray: Some(Ray::new(hit.point.clone(), direction, ray.departure_time)),
+        pdf: Box::new(pdf),
+        skip_pdf: false,
+    })
+}
+
+/**
+ * Scattering function times cosine and sampling density of `ggx_transmit()`
+ * into the given direction, both for the reflected and the refracted part.
+ * Eta is the refractive index ratio as in `ggx_transmit()`.
+ */
+#[allow(clippy::similar_names)] // the cosines of wo and wi with the microfacet normal
+pub fn ggx_transmit_eval(pdf: &GgxPdf, direction: &Vec3, eta: f64) -> (f64, f64) {
+    let wo = pdf.wo();
+    let wi = pdf.to_local(&direction.unit());
+
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    if wi.z > 0.0 {
+        let m = (wo + &wi).unit();
+        let cos_om = wo.dot(&m);
+        if cos_om <= 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let fresnel = fresnel_dielectric(cos_om, eta);
+        let value = fresnel * pdf.distribution(&m) * pdf.g2(wo, &wi) / (4.0 * wo.z);
+        return (value, fresnel * pdf.normal_pdf(&m) / (4.0 * cos_om));
+    }
+
+    // the generalized half vector of the refraction
+    let m = (wo + eta * &wi).unit();
+    let m = if m.z < 0.0 { -m } else { m };
+    let cos_om = wo.dot(&m);
+    let cos_im = wi.dot(&m);
+    if cos_om <= 0.0 || cos_im >= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let transmitted = 1.0 - fresnel_dielectric(cos_om, eta);
+    let denominator = eta.mul_add(cos_im, cos_om);
+    // jacobian of the refraction at the microfacet
+    let jacobian = eta * eta * -cos_im / (denominator * denominator);
+
+    let value = transmitted * pdf.distribution(&m) * pdf.g2(wo, &wi) * cos_om * jacobian / wo.z;
+    (value, transmitted * pdf.normal_pdf(&m) * jacobian)
+}
+
+
+/**
+ * A rough metal surface with a GGX microfacet distribution. By default the
+ * texture color is the reflectance at normal incidence (Schlick's Fresnel
+ * approximation), with a complex refractive index the exact conductor
+ * Fresnel term is used and the texture just tints the reflection.
+ */
+pub struct GgxConductor {
+    texture: Arc<dyn Texture>,
+    roughness: f64,
+    // complex refractive index per color channel, real and imaginary part
+    ior: Option<(Vec3, Vec3)>,
+    settings: CommonMaterialSettings,
+}
+
+impl Debug for GgxConductor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "GgxConductor {{ roughness: {}, ior: {:?} }}", self.roughness, self.ior
+        ))
+    }
+}
+
+impl GgxConductor {
+    #[must_use]
+    pub fn new(texture: Arc<dyn Texture>, roughness: f64) -> Self {
+        Self {
+            texture,
+            roughness,
+            ior: None,
+            settings: CommonMaterialSettings::new(),
+        }
+    }
+
+    /**
+     * A conductor with complex refractive index eta + i*k per color channel,
+     * e.g. gold is about eta = <0.18, 0.42, 1.37>, k = <3.42, 2.35, 1.77>
+     */
+    #[must_use]
+    pub fn from_ior(eta: Vec3, k: Vec3, roughness: f64) -> Self {
+        Self::new(Arc::new(Color::new(1.0, 1.0, 1.0, 1.0)), roughness).complex_ior(eta, k)
+    }
+
+    #[must_use]
+    pub const fn complex_ior(mut self, eta: Vec3, k: Vec3) -> Self {
+        self.ior = Some((eta, k));
+        self
+    }
+
+    fn fresnel(&self, cos_i: f64, tint: Vec3) -> Vec3 {
+        match &self.ior {
+            Some((eta, k)) => {
+                tint * Vec3::new(
+                    fresnel_conductor(cos_i, eta.x, k.x),
+                    fresnel_conductor(cos_i, eta.y, k.y),
+                    fresnel_conductor(cos_i, eta.z, k.z))
+            },
+            None => fresnel_schlick(cos_i, &tint),
+        }
+    }
+}
+
+impl Material for GgxConductor {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = FastRng::for_hit(ray, hit.t1);
+        let tint: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+
+        ggx_reflect(ray, hit, self.roughness, |cos| self.fresnel(cos, tint.clone()), &mut rng)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        let tint: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+        let pdf = GgxPdf::new(&hit.normal, &-ray_in.direction.unit(), self.roughness);
+
+        ggx_reflect_eval(&pdf, &ray_out.direction, |cos| self.fresnel(cos, tint.clone()))
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        GgxPdf::new(&hit.normal, &-ray_in.direction.unit(), self.roughness).value(&ray_out.direction.unit())
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.settings.clone()
+    }
+
+    fn set(&mut self, settings: CommonMaterialSettings) {
+        self.settings = settings;
+    }
+}
+
+
+/**
+ * Brushed metal with an anisotropic GGX microfacet distribution. The
+ * roughness along the tangent of the surface and along the bitangent differ,
+ * which stretches highlights across the direction of the brushing. The
+ * texture color is the reflectance at normal incidence.
+ */
+pub struct AnisotropicMetal {
+    texture: Arc<dyn Texture>,
+    roughness_u: f64,
+    roughness_v: f64,
+    settings: CommonMaterialSettings,
+}
+
+impl Debug for AnisotropicMetal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "AnisotropicMetal {{ roughness_u: {}, roughness_v: {} }}", self.roughness_u, self.roughness_v
+        ))
+    }
+}
+
+impl AnisotropicMetal {
+    #[must_use]
+    pub fn new(texture: Arc<dyn Texture>, roughness_u: f64, roughness_v: f64) -> Self {
+        Self {
+            texture,
+            roughness_u,
+            roughness_v,
+            settings: CommonMaterialSettings::new(),
+        }
+    }
+
+    fn distribution(&self, ray: &Ray, hit: &HitRecord) -> GgxPdf {
+        GgxPdf::anisotropic(&hit.normal, &hit.tangent, &-ray.direction.unit(),
+                            self.roughness_u, self.roughness_v)
+    }
+}
+
+impl Material for AnisotropicMetal {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = FastRng::for_hit(ray, hit.t1);
+        let f0: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+
+        ggx_reflect_with(self.distribution(ray, hit), ray, hit, |cos| fresnel_schlick(cos, &f0), &mut rng)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        let f0: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+
+        ggx_reflect_eval(&self.distribution(ray_in, hit), &ray_out.direction, |cos| fresnel_schlick(cos, &f0))
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        self.distribution(ray_in, hit).value(&ray_out.direction.unit())
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.settings.clone()
+    }
+
+    fn set(&mut self, settings: CommonMaterialSettings) {
+        self.settings = settings;
+    }
+}
+
+
+/**
+ * Rough glass with a GGX microfacet distribution. Light is either reflected
+ * or refracted at a sampled microfacet, chosen by the Fresnel reflectance.
+ */
+#[derive(Debug)]
+pub struct GgxDielectric {
+    color: Color,
+    refractive: f64,
+    roughness: f64,
+    settings: CommonMaterialSettings,
+}
+
+impl GgxDielectric {
+    #[must_use]
+    pub fn new(color: Color, refractive: f64, roughness: f64) -> Self {
+        Self {
+            color,
+            refractive,
+            roughness,
+            settings: CommonMaterialSettings::new(),
+        }
+    }
+
+    fn distribution(&self, ray: &Ray, hit: &HitRecord) -> GgxPdf {
+        GgxPdf::new(&hit.normal, &-ray.direction.unit(), self.roughness)
+    }
+
+    fn eta(&self, hit: &HitRecord) -> f64 {
+        if hit.outside { self.refractive } else { 1.0 / self.refractive }
+    }
+}
+
+impl Material for GgxDielectric {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = FastRng::for_hit(ray, hit.t1);
+
+        ggx_transmit(ray, hit, self.color.clone(), self.refractive, self.roughness, &mut rng)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        let (value, _) = ggx_transmit_eval(&self.distribution(ray_in, hit), &ray_out.direction, self.eta(hit));
+        Vec3::from(self.color.clone()) * value
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        ggx_transmit_eval(&self.distribution(ray_in, hit), &ray_out.direction, self.eta(hit)).1
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.settings.clone()
+    }
+
+    fn set(&mut self, settings: CommonMaterialSettings) {
+        self.settings = settings;
+    }
+}
@@ -1,7 +1,6 @@
 use std::sync::Arc;
-use rand::thread_rng;
-use rand::RngCore;
 
+use crate::prelude::FastRng;
 use crate::prelude::Point3;
 use crate::prelude::Vec3;
 use crate::prelude::Ray;
@@ -14,6 +13,9 @@ use crate::material::HitRecord;
 use std::fmt::Formatter;
 use std::fmt::Debug;
 
+// separates the choice of the material from the choices of the materials
+const SALT: u64 = 0x6d69_7865_645f_6d61;
+
 pub struct MixedMaterial {
     material_1: Arc<dyn Material>,
     material_2: Arc<dyn Material>,
@@ -36,23 +38,43 @@ impl MixedMaterial {
             probability_1,
         }
     }
+
+    // the same ray makes the same choice at the same hit, for
+    // scatter() and for opaque()
+    fn choose(&self, ray: &Ray, hit: &HitRecord) -> &Arc<dyn Material> {
+        if FastRng::for_hit_salted(ray, hit.t1, SALT).gen() < self.probability_1 {
+            &self.material_1
+        }
+        else {
+            &self.material_2
+        }
+    }
 }
 
 impl Material for MixedMaterial {
 
     fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
-        if (thread_rng().next_u32() as f64) < u32::MAX as f64 * self.probability_1 {
-            self.material_1.scatter(ray, hit)
-        }
-        else {
-            self.material_2.scatter(ray, hit)
-        }
+        self.choose(ray, hit).scatter(ray, hit)
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        self.material_1.eval(ray_in, hit, ray_out) * self.probability_1
+            + self.material_2.eval(ray_in, hit, ray_out) * (1.0 - self.probability_1)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        self.material_1.pdf(ray_in, hit, ray_out)
+            .mul_add(self.probability_1, self.material_2.pdf(ray_in, hit, ray_out) * (1.0 - self.probability_1))
     }
 
     fn emitted(&self, _u: f64, _v: f64, _point: &Point3) -> Option<Vec3> {
         None
     }
 
+    fn opaque(&self, ray: &Ray, hit: &HitRecord) -> bool {
+        self.choose(ray, hit).opaque(ray, hit)
+    }
+
     fn settings(&self) -> CommonMaterialSettings {
         self.material_1.settings()
     }
@@ -10,20 +10,39 @@ pub(crate) mod isotropic;
 pub(crate) mod lambertian;
 pub(crate) mod light;
 pub(crate) mod metal;
+pub(crate) mod microfacet;
 pub(crate) mod blinn_phong;
+pub(crate) mod cutout;
 pub(crate) mod mixed_material;
+pub(crate) mod phase;
+pub(crate) mod principled;
+pub(crate) mod subsurface;
+pub(crate) mod thin_film;
 
 pub use {
-    dielectric::{Dielectric, Glass},
+    dielectric::{Dielectric, Dispersion, Glass},
     isotropic::Isotropic,
     lambertian::Lambertian,
     light::DiffuseLight,
     metal::{Metal, DiffuseMetal},
+    microfacet::{GgxConductor, GgxDielectric, AnisotropicMetal},
     blinn_phong::BlinnPhong,
+    cutout::Cutout,
     mixed_material::MixedMaterial,
+    phase::PhaseFunction,
+    principled::Principled,
+    subsurface::Subsurface,
+    thin_film::ThinFilm,
 };
 
 
+/**
+ * The ray a material scatters into. Specular records (`skip_pdf`) carry a
+ * ray into a single direction, which no density covers. Other records
+ * carry a ray the material sampled itself, e.g. from a microfacet lobe,
+ * or leave the ray to be drawn from `pdf`. The color is the path weight
+ * of the ray, the scattering function times cosine per density.
+ */
 pub struct ScatterRecord {
     pub color: Color,
     pub ray: Option<Ray>,
@@ -64,6 +83,55 @@ pub trait Material: Send + Sync {
         None
     }
 
+    /**
+     * The scattering function for light arriving along `ray_in` and leaving
+     * along `ray_out`, times the cosine between `ray_out` and the normal.
+     * This is what a scattered ray of the given direction carries per
+     * density, i.e. `eval / pdf` is the weight `scatter()` gives the ray.
+     *
+     * The result must not be random. The default derives it from
+     * `scatter()`, which is exact for materials with a single lobe.
+     * Materials which choose between lobes randomly in `scatter()` override
+     * `eval()` and `pdf()` with the sum of their lobes, weighted by the
+     * probabilities of the choice. Materials which only scatter into single
+     * directions (mirrors, glass) give zero.
+     */
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        match self.scatter(ray_in, hit) {
+            Some(srec) if !srec.skip_pdf => {
+                let pdf = srec.pdf.value(&ray_out.direction);
+                if pdf > 0.0 { Vec3::from(srec.color) * pdf } else { Vec3::default() }
+            },
+            _ => Vec3::default(),
+        }
+    }
+
+    /**
+     * Density by solid angle with which `scatter()` picks the direction of
+     * `ray_out` for light arriving along `ray_in`. Zero for directions which
+     * can't be sampled and for single direction (specular) scattering. Like
+     * `eval()` it must not be random.
+     */
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        match self.scatter(ray_in, hit) {
+            Some(srec) if !srec.skip_pdf => srec.pdf.value(&ray_out.direction).max(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// Materials which emit light make their objects light sources
+    fn emissive(&self) -> bool {
+        false
+    }
+
+    /**
+     * Whether the surface blocks the ray at the hit. Rays pass through
+     * the cut out parts of a surface, see `Cutout`.
+     */
+    fn opaque(&self, _ray: &Ray, _hit: &HitRecord) -> bool {
+        true
+    }
+
     fn settings(&self) -> CommonMaterialSettings;
 
     fn set(&mut self, settings: CommonMaterialSettings) {
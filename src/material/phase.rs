@@ -0,0 +1,36 @@
+use crate::prelude::*;
+
+
+/**
+ * How participating media scatter light. The phase function is relative to
+ * the direction of the incoming ray, so the forward scattering of fog and
+ * clouds mostly keeps the light moving on.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum PhaseFunction {
+    /// Uniform scattering into all directions
+    #[default]
+    Isotropic,
+
+    /// Henyey-Greenstein lobe with asymmetry g in range -1 .. 1, positive values scatter forward
+    HenyeyGreenstein(f64),
+
+    /// Two Henyey-Greenstein lobes, weight is the share of the first lobe
+    DoubleLobe { g1: f64, g2: f64, weight: f64 },
+}
+
+impl PhaseFunction {
+
+    /**
+     * The distribution of scattered directions for light travelling
+     * into the given direction.
+     */
+    #[must_use]
+    pub fn pdf(&self, direction: &Vec3) -> Box<dyn PDF> {
+        match *self {
+            Self::Isotropic => Box::new(SpherePdf::new()),
+            Self::HenyeyGreenstein(g) => Box::new(HenyeyGreensteinPdf::new(direction, g)),
+            Self::DoubleLobe { g1, g2, weight } => Box::new(DoubleLobePdf::new(direction, g1, g2, weight)),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Material, ScatterRecord},
+    material::microfacet::{
+        fresnel_dielectric, fresnel_schlick, ggx_reflect, ggx_reflect_eval, ggx_transmit, ggx_transmit_eval,
+    },
+    prelude::*,
+    texture::Texture,
+};
+use crate::material::CommonMaterialSettings;
+
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+
+/**
+ * A principled (Disney style) material, which covers most real world
+ * surfaces with a few intuitive parameters in range 0 .. 1. All parameters
+ * are textures, the red channel is used for the scalar ones. Scalar
+ * constants can be given as plain f64 values.
+ *
+ * For each scattering event one lobe is chosen randomly: the clearcoat
+ * layer on top, the metal reflection, the transmission, the specular
+ * reflection of the dielectric base, the sheen or the diffuse base.
+ * `eval()` and `pdf()` add up all lobes, weighted by the same
+ * probabilities.
+ */
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    metallic: Arc<dyn Texture>,
+    roughness: Arc<dyn Texture>,
+    specular: Arc<dyn Texture>,
+    sheen: Arc<dyn Texture>,
+    clearcoat: Arc<dyn Texture>,
+    transmission: Arc<dyn Texture>,
+    clearcoat_roughness: f64,
+    refractive: f64,
+    settings: CommonMaterialSettings,
+}
+
+impl Debug for Principled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Principled {{ refractive: {} }}", self.refractive
+        ))
+    }
+}
+
+impl Principled {
+    #[must_use]
+    pub fn new(base_color: Arc<dyn Texture>) -> Self {
+        Self {
+            base_color,
+            metallic: Arc::new(0.0),
+            roughness: Arc::new(0.5),
+            specular: Arc::new(0.5),
+            sheen: Arc::new(0.0),
+            clearcoat: Arc::new(0.0),
+            transmission: Arc::new(0.0),
+            clearcoat_roughness: 0.03,
+            refractive: 1.5,
+            settings: CommonMaterialSettings::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn metallic<T: Texture + 'static>(mut self, metallic: T) -> Self {
+        self.metallic = Arc::new(metallic);
+        self
+    }
+
+    #[must_use]
+    pub fn roughness<T: Texture + 'static>(mut self, roughness: T) -> Self {
+        self.roughness = Arc::new(roughness);
+        self
+    }
+
+    /// Strength of the specular reflection of non-metals, 0.5 is a reflectance of 4%,
+    /// 0 turns the reflection off
+    #[must_use]
+    pub fn specular<T: Texture + 'static>(mut self, specular: T) -> Self {
+        self.specular = Arc::new(specular);
+        self
+    }
+
+    /// Soft white reflection at grazing angles, like on velvet. It takes the
+    /// place of the diffuse base there, so the albedo stays at most 1
+    #[must_use]
+    pub fn sheen<T: Texture + 'static>(mut self, sheen: T) -> Self {
+        self.sheen = Arc::new(sheen);
+        self
+    }
+
+    /// Strength of a glossy lacquer layer on top of the surface
+    #[must_use]
+    pub fn clearcoat<T: Texture + 'static>(mut self, clearcoat: T) -> Self {
+        self.clearcoat = Arc::new(clearcoat);
+        self
+    }
+
+    #[must_use]
+    pub fn transmission<T: Texture + 'static>(mut self, transmission: T) -> Self {
+        self.transmission = Arc::new(transmission);
+        self
+    }
+
+    #[must_use]
+    pub const fn clearcoat_roughness(mut self, roughness: f64) -> Self {
+        self.clearcoat_roughness = roughness;
+        self
+    }
+
+    /// The refractive index of transmitting surfaces
+    #[must_use]
+    pub const fn refractive(mut self, refractive: f64) -> Self {
+        self.refractive = refractive;
+        self
+    }
+}
+
+fn scalar(texture: &Arc<dyn Texture>, hit: &HitRecord) -> f64 {
+    clamp(f64::from(texture.color(hit.u, hit.v, &hit.point).r), 0.0 ..= 1.0)
+}
+
+
+// the probabilities of the lobes for one outgoing direction, they are
+// chosen by their reflectance, so the reflectance cancels out of the
+// path weight of a sampled lobe
+struct Lobes {
+    base_color: Color,
+    roughness: f64,
+    clearcoat: f64,
+    metallic: f64,
+    transmission: f64,
+    specular: f64,
+    sheen: f64,
+    diffuse: f64,
+}
+
+impl Principled {
+    fn lobes(&self, ray: &Ray, hit: &HitRecord) -> Lobes {
+        let cos_o = clamp((-ray.direction.unit()).dot(&hit.normal), 0.0 ..= 1.0);
+        let grazing = (1.0 - cos_o).powi(5);
+
+        // each layer takes its share of what the layers above let through
+        let clearcoat = scalar(&self.clearcoat, hit) * fresnel_dielectric(cos_o, 1.5);
+        let mut rest = 1.0 - clearcoat;
+
+        let metallic = rest * scalar(&self.metallic, hit);
+        rest -= metallic;
+
+        let transmission = rest * scalar(&self.transmission, hit);
+        rest -= transmission;
+
+        // Schlick's approximation with a reflectance of 8% at normal incidence
+        // for specular 1, scaled as a whole so specular 0 has no reflection
+        let reflectance = (2.0 * scalar(&self.specular, hit) * 0.96f64.mul_add(grazing, 0.04)).min(1.0);
+        let specular = rest * reflectance;
+        rest -= specular;
+
+        let sheen = rest * scalar(&self.sheen, hit) * grazing;
+
+        Lobes {
+            base_color: self.base_color.color(hit.u, hit.v, &hit.point),
+            roughness: scalar(&self.roughness, hit),
+            clearcoat,
+            metallic,
+            transmission,
+            specular,
+            sheen,
+            diffuse: rest - sheen,
+        }
+    }
+
+    fn eta(&self, hit: &HitRecord) -> f64 {
+        if hit.outside { self.refractive } else { 1.0 / self.refractive }
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = FastRng::for_hit(ray, hit.t1);
+        let lobes = self.lobes(ray, hit);
+        let white = Vec3::new(1.0, 1.0, 1.0);
+
+        let mut choice = rng.gen();
+
+        if choice < lobes.clearcoat {
+            return ggx_reflect(ray, hit, self.clearcoat_roughness, |_| white.clone(), &mut rng);
+        }
+        choice -= lobes.clearcoat;
+
+        if choice < lobes.metallic {
+            let f0: Vec3 = lobes.base_color.into();
+            return ggx_reflect(ray, hit, lobes.roughness, |cos| fresnel_schlick(cos, &f0), &mut rng);
+        }
+        choice -= lobes.metallic;
+
+        if choice < lobes.transmission {
+            return ggx_transmit(ray, hit, lobes.base_color, self.refractive, lobes.roughness, &mut rng);
+        }
+        choice -= lobes.transmission;
+
+        if choice < lobes.specular {
+            return ggx_reflect(ray, hit, lobes.roughness, |_| white.clone(), &mut rng);
+        }
+        choice -= lobes.specular;
+
+        // the sheen is a white diffuse lobe which replaces
+        // the base color at grazing angles
+        let color = if choice < lobes.sheen { Color::new(1.0, 1.0, 1.0, 1.0) } else { lobes.base_color };
+
+        Some(ScatterRecord {
+            color,
+            ray: None,
+            pdf: Box::new(CosinePdf::new(&hit.normal)),
+            skip_pdf: false,
+        })
+    }
+
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        let lobes = self.lobes(ray_in, hit);
+        let wo = -ray_in.direction.unit();
+        let white = |_| Vec3::new(1.0, 1.0, 1.0);
+        let base: Vec3 = lobes.base_color.into();
+
+        let coat = GgxPdf::new(&hit.normal, &wo, self.clearcoat_roughness);
+        let pdf = GgxPdf::new(&hit.normal, &wo, lobes.roughness);
+
+        let cos_i = ray_out.direction.unit().dot(&hit.normal).max(0.0);
+        let diffuse = (Vec3::new(lobes.sheen, lobes.sheen, lobes.sheen) + &base * lobes.diffuse) * (cos_i / PI);
+
+        let (transmitted, _) = ggx_transmit_eval(&pdf, &ray_out.direction, self.eta(hit));
+
+        ggx_reflect_eval(&coat, &ray_out.direction, white) * lobes.clearcoat
+            + ggx_reflect_eval(&pdf, &ray_out.direction, |cos| fresnel_schlick(cos, &base)) * lobes.metallic
+            + &base * (transmitted * lobes.transmission)
+            + ggx_reflect_eval(&pdf, &ray_out.direction, white) * lobes.specular
+            + diffuse
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        let lobes = self.lobes(ray_in, hit);
+        let wo = -ray_in.direction.unit();
+        let direction = ray_out.direction.unit();
+
+        let coat = GgxPdf::new(&hit.normal, &wo, self.clearcoat_roughness);
+        let pdf = GgxPdf::new(&hit.normal, &wo, lobes.roughness);
+        let (_, transmitted) = ggx_transmit_eval(&pdf, &direction, self.eta(hit));
+        let cos_i = direction.dot(&hit.normal).max(0.0);
+
+        let reflected = pdf.value(&direction) * (lobes.metallic + lobes.specular);
+        let glossy = coat.value(&direction).mul_add(lobes.clearcoat, reflected);
+        let glossy = transmitted.mul_add(lobes.transmission, glossy);
+
+        (cos_i / PI).mul_add(lobes.sheen + lobes.diffuse, glossy)
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.settings.clone()
+    }
+
+    fn set(&mut self, settings: CommonMaterialSettings) {
+        self.settings = settings;
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Dielectric, Glass, Material, PhaseFunction, ScatterRecord},
+    prelude::*,
+};
+use crate::material::CommonMaterialSettings;
+
+
+// separates the random walk from the choices of the boundary
+const SALT: u64 = 0x7375_6273_7572_6661;
+
+
+/**
+ * Subsurface scattering for skin, wax, marble and milk. Light refracts into
+ * the object at a dielectric boundary and then does a random walk inside:
+ * the free path lengths between scattering events follow an exponential
+ * distribution with the given mean, and each event tints the light with
+ * the albedo. The walk needs no knowledge of the object, the rays inside
+ * simply hit the boundary of the enclosing object from the inside again.
+ *
+ * Long random walks need a high path depth, objects nested inside a
+ * subsurface object cut the walk short.
+ */
+#[derive(Debug)]
+pub struct Subsurface {
+    albedo: Color,
+    mean_free_path: f64,
+    phase: PhaseFunction,
+    boundary: Dielectric,
+    settings: CommonMaterialSettings,
+}
+
+impl Subsurface {
+    #[must_use]
+    pub fn new(albedo: Color, mean_free_path: f64) -> Self {
+        Self {
+            albedo,
+            mean_free_path,
+            phase: PhaseFunction::Isotropic,
+            boundary: Dielectric::new(Color::new(1.0, 1.0, 1.0, 1.0), 1.3).reflect_curve(Glass {}),
+            settings: CommonMaterialSettings::new(),
+        }
+    }
+
+    /**
+     * The surface through which light enters and leaves the object, by
+     * default a clear dielectric with refractive index 1.3. Absorption of
+     * the boundary applies to the free paths which reach the surface only,
+     * the albedo is the better place for the color of the object.
+     */
+    #[must_use]
+    pub fn boundary(mut self, boundary: Dielectric) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    #[must_use]
+    pub const fn phase(mut self, phase: PhaseFunction) -> Self {
+        self.phase = phase;
+        self
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        if hit.outside {
+            return self.boundary.scatter(ray, hit);
+        }
+
+        // the ray started at the boundary or at the last scattering
+        // event inside, so it travelled t1 to reach the surface again
+        let mut rng = FastRng::for_hit_salted(ray, hit.t1, SALT);
+        let length_per_unit = ray.direction.length();
+        let free_path = -(1.0 - rng.gen()).ln() * self.mean_free_path;
+
+        if free_path >= hit.t1 * length_per_unit {
+            return self.boundary.scatter(ray, hit);
+        }
+
+        let point = ray.at(free_path / length_per_unit);
+        let pdf = self.phase.pdf(&ray.direction);
+        let direction = pdf.generate(&mut rng).unit();
+
+        Some(ScatterRecord {
+            color: self.albedo.clone(),
+            ray: Some(Ray::new(point, direction, ray.departure_time)),
+            pdf,
+            skip_pdf: true,
+        })
+    }
+
+    // the boundary is a perfect dielectric and the random walk scatters
+    // away from the hit, so no direction at the hit can be evaluated
+    fn eval(&self, _ray_in: &Ray, _hit: &HitRecord, _ray_out: &Ray) -> Vec3 {
+        Vec3::default()
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _hit: &HitRecord, _ray_out: &Ray) -> f64 {
+        0.0
+    }
+
+    fn opaque(&self, ray: &Ray, hit: &HitRecord) -> bool {
+        self.boundary.opaque(ray, hit)
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.settings.clone()
+    }
+
+    fn set(&mut self, settings: CommonMaterialSettings) {
+        self.settings = settings;
+    }
+}
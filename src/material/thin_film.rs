@@ -0,0 +1,186 @@
+use crate::{
+    hittable::HitRecord,
+    material::{reflect, Material, ScatterRecord},
+    prelude::*,
+    texture::Texture,
+};
+use crate::material::CommonMaterialSettings;
+
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+
+// wavelengths in nm which stand for the color channels in RGB mode
+const RGB_WAVELENGTHS: [f64; 3] = [650.0, 532.0, 450.0];
+
+// separates the choice at the film from the choices of the wrapped material
+const SALT: u64 = 0x7468_696e_5f66_696c;
+
+
+/**
+ * Reflectance of a thin film between two media for unpolarized light,
+ * including the interference of the light reflected at both sides of the
+ * film. The refractive indices are outside, of the film and below the film.
+ * Thickness and wavelength are in nanometers.
+ */
+fn thin_film_reflectance(cos_i: f64, refractive: [f64; 3], thickness: f64, wavelength: f64) -> f64 {
+    let [outer, film, inner] = refractive;
+    let cos1 = cos_i.clamp(0.0, 1.0);
+    let sin2_1 = 1.0 - cos1 * cos1;
+
+    let sin2_2 = sin2_1 * (outer / film).powi(2);
+    let sin2_3 = sin2_1 * (outer / inner).powi(2);
+    if sin2_2 >= 1.0 || sin2_3 >= 1.0 {
+        // total internal reflection
+        return 1.0;
+    }
+    let cos2 = (1.0 - sin2_2).sqrt();
+    let cos3 = (1.0 - sin2_3).sqrt();
+
+    let r12_s = outer.mul_add(cos1, -(film * cos2)) / outer.mul_add(cos1, film * cos2);
+    let r23_s = film.mul_add(cos2, -(inner * cos3)) / film.mul_add(cos2, inner * cos3);
+    let r12_p = film.mul_add(cos1, -(outer * cos2)) / film.mul_add(cos1, outer * cos2);
+    let r23_p = inner.mul_add(cos2, -(film * cos3)) / inner.mul_add(cos2, film * cos3);
+
+    // phase difference of the light reflected at the back of the film
+    let cos_delta = (4.0 * PI * film * thickness * cos2 / wavelength).cos();
+
+    let airy = |r12: f64, r23: f64| {
+        let cross = 2.0 * r12 * r23 * cos_delta;
+        let product = r12 * r23;
+        (r12.mul_add(r12, r23 * r23) + cross) / product.mul_add(product, 1.0 + cross)
+    };
+
+    clamp(0.5 * (airy(r12_s, r23_s) + airy(r12_p, r23_p)), 0.0 ..= 1.0)
+}
+
+
+/**
+ * An iridescent coating like on soap bubbles, oil slicks or coated lenses.
+ * The film reflects light like a mirror with a wavelength dependent
+ * reflectance, the transmitted light is handled by the wrapped material.
+ *
+ * The film thickness is given in nanometers, an optional texture scales it
+ * by its red channel, e.g. a `Perlin` noise for swirling bubbles. In RGB mode
+ * the reflectance is evaluated at one wavelength per color channel, in
+ * spectral mode at the wavelength of the ray.
+ */
+pub struct ThinFilm {
+    material: Arc<dyn Material>,
+    thickness: f64,
+    thickness_texture: Option<Arc<dyn Texture>>,
+    refractive: f64,
+    substrate: f64,
+}
+
+impl Debug for ThinFilm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "ThinFilm {{ thickness: {}, refractive: {}, substrate: {} }}",
+            self.thickness, self.refractive, self.substrate
+        ))
+    }
+}
+
+impl ThinFilm {
+    #[must_use]
+    pub fn new(material: Arc<dyn Material>, thickness: f64, refractive: f64) -> Self {
+        Self {
+            material,
+            thickness,
+            thickness_texture: None,
+            refractive,
+            substrate: 1.5,
+        }
+    }
+
+    #[must_use]
+    pub fn thickness_texture<T: Texture + 'static>(mut self, texture: T) -> Self {
+        self.thickness_texture = Some(Arc::new(texture));
+        self
+    }
+
+    /// The refractive index below the film, 1.0 for soap bubbles
+    #[must_use]
+    pub const fn substrate(mut self, substrate: f64) -> Self {
+        self.substrate = substrate;
+        self
+    }
+
+    fn thickness_at(&self, hit: &HitRecord) -> f64 {
+        self.thickness_texture.as_ref().map_or(self.thickness, |texture| {
+            self.thickness * f64::from(texture.color(hit.u, hit.v, &hit.point).r).max(0.0)
+        })
+    }
+
+    // reflectance of the film per color channel, and the probability to reflect
+    fn reflectance(&self, ray: &Ray, hit: &HitRecord) -> (Vec3, f64) {
+        let cos_i = (-&ray.direction).dot(&hit.normal) / ray.direction.length();
+        let thickness = self.thickness_at(hit);
+        let (outer, inner) = if hit.outside { (1.0, self.substrate) } else { (self.substrate, 1.0) };
+
+        let reflectance = |wavelength| thin_film_reflectance(cos_i, [outer, self.refractive, inner], thickness, wavelength);
+
+        // one wavelength per color channel, or the wavelength of the ray for all of them
+        let wavelengths = ray.wavelength.map_or(RGB_WAVELENGTHS, |wavelength| [wavelength; 3]);
+        let r = Vec3::new(reflectance(wavelengths[0]), reflectance(wavelengths[1]), reflectance(wavelengths[2]));
+
+        // reflect or pass the film by the mean reflectance, the
+        // weights restore the reflectance of each channel
+        let p = clamp((r.x + r.y + r.z) / 3.0, 0.001 ..= 0.999);
+
+        (r, p)
+    }
+}
+
+impl Material for ThinFilm {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut rng = FastRng::for_hit_salted(ray, hit.t1, SALT);
+        let (r, p) = self.reflectance(ray, hit);
+
+        if rng.gen() < p {
+            let weight = r / p;
+
+            return Some(ScatterRecord {
+                color: Color::new64(weight.x, weight.y, weight.z, 1.0),
+                ray: Some(reflect(ray, hit)),
+                pdf: Box::new(CosinePdf::new(&hit.normal)),
+                skip_pdf: true,
+            });
+        }
+
+        let weight = (Vec3::new(1.0, 1.0, 1.0) - r) / (1.0 - p);
+        let mut srec = self.material.scatter(ray, hit)?;
+        srec.color = srec.color * Color::new64(weight.x, weight.y, weight.z, 1.0);
+
+        Some(srec)
+    }
+
+    // the mirror reflection of the film has no part in these
+    fn eval(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> Vec3 {
+        let (r, _) = self.reflectance(ray_in, hit);
+        (Vec3::new(1.0, 1.0, 1.0) - r) * self.material.eval(ray_in, hit, ray_out)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit: &HitRecord, ray_out: &Ray) -> f64 {
+        let (_, p) = self.reflectance(ray_in, hit);
+        (1.0 - p) * self.material.pdf(ray_in, hit, ray_out)
+    }
+
+    fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
+        self.material.emitted(u, v, point)
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.emissive()
+    }
+
+    fn opaque(&self, ray: &Ray, hit: &HitRecord) -> bool {
+        self.material.opaque(ray, hit)
+    }
+
+    fn settings(&self) -> CommonMaterialSettings {
+        self.material.settings()
+    }
+}
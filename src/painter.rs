@@ -73,6 +73,7 @@ pub struct Painter {
     gamma: bool,
     threads: usize,
     parallel: bool,
+    seed: Option<u64>,
 
     sqrt_spp: usize,         // Square root of number of samples per pixel
 }
@@ -95,6 +96,7 @@ impl Painter {
             samples: 25,
             threads: 0,
             parallel: true,
+            seed: None,
 
             sqrt_spp: 5,
         }
@@ -129,6 +131,16 @@ impl Painter {
         self
     }
 
+    /**
+     * With a seed, each row gets its own random number generator, which makes
+     * the image independent of the number of threads and their timing.
+     */
+    #[must_use]
+    pub const fn seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
     #[allow(clippy::cast_precision_loss)] // because row and column is small enough in practice
     fn calculate_uv(&self, x: f64, y: f64) -> [f64; 2]  {
 
@@ -246,6 +258,10 @@ impl Painter {
         let mut pixels = Vec::new();
 
         for y in (row .. self.height).step_by(step ) {
+            if let Some(seed) = self.seed {
+                rng = FastRng::new_seeded(seed ^ (y as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            }
+
             let mut row_pixels = self.render_row(y, uv_color, cancel, target, pixel_map, &mut rng);
             pixels.append(&mut row_pixels);
         } 
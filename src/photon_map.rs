@@ -0,0 +1,345 @@
+use {
+    crate::{
+        hittable::{collection::World, HitRecord, Hittable},
+        material::Material,
+        prelude::*,
+    },
+    log::info,
+    std::{
+        cmp::Ordering,
+        collections::BinaryHeap,
+        fmt::{Debug, Formatter},
+        sync::Arc,
+    },
+};
+
+
+/// Light which arrived at a diffuse surface after specular bounces
+#[derive(Debug, Clone)]
+pub struct Photon {
+    pub point: Point3,
+    // direction of travel of the photon
+    pub direction: Vec3,
+    pub power: Vec3,
+}
+
+
+// the nearest photons found so far, the farthest on top of the heap
+struct Search {
+    heap: BinaryHeap<Neighbour>,
+    max_squared: f64,
+}
+
+// entry of the search heap, ordered by the squared distance
+struct Neighbour {
+    distance_squared: f64,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance_squared == other.distance_squared
+    }
+}
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_squared.total_cmp(&other.distance_squared)
+    }
+}
+
+
+/**
+ * A caustics photon map. Photons are emitted from the lights of the world,
+ * follow specular bounces (all materials which sample their own scattered
+ * ray) and are stored where they land on a diffuse surface. Photons which
+ * reach a diffuse surface directly are not stored, the path tracer finds
+ * their light by light sampling already.
+ *
+ * The photons are kept in a balanced kd-tree: the median of each range is
+ * the node, splitting the range along the axis of the largest extent.
+ */
+pub struct PhotonMap {
+    photons: Vec<Photon>,
+    // materials of the lights which emit photons
+    emitters: Vec<Arc<dyn Material>>,
+    axes: Vec<usize>,
+    radius: f64,
+    neighbours: usize,
+}
+
+impl Debug for PhotonMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "PhotonMap {{ photons: {}, radius: {}, neighbours: {} }}",
+            self.photons.len(), self.radius, self.neighbours
+        ))
+    }
+}
+
+impl PhotonMap {
+    /**
+     * Emits the given number of photons from the lights of the world. Only
+     * lights which can sample their surface emit photons. Without a radius,
+     * a hundredth of the size of the scene is used as gathering radius.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(world: &World, count: usize, radius: Option<f64>, seed: Option<u64>) -> Self {
+        let lights: Vec<_> = world.lights.iter().filter(|light| light.emissive()).collect();
+
+        let radius = radius.unwrap_or_else(|| {
+            world.bbox(&(0.0 .. 0.0))
+                .map_or(1.0, |bbox| (&bbox.max - &bbox.min).length() * 0.01)
+        });
+
+        let mut photons = Vec::new();
+
+        let mut rng = FastRng::new_seeded(seed.unwrap_or(0));
+        let emitters = lights
+            .iter()
+            .filter_map(|light| {
+                let sample = light.sample_surface(&mut rng)?;
+                probe(light.as_ref(), &sample.point, &sample.normal)?.material
+            })
+            .collect();
+
+        if !lights.is_empty() && count > 0 {
+            let threads = num_cpus::get().max(1);
+            let per_thread = count / threads + 1;
+
+            // each emitted photon carries an equal share of the power of all lights
+            let share = lights.len() as f64 / (per_thread * threads) as f64;
+
+            std::thread::scope(|scope| {
+                let workers: Vec<_> = (0 .. threads)
+                    .map(|i| {
+                        let lights = &lights;
+                        scope.spawn(move || {
+                            let mut rng = seed.map_or_else(FastRng::new, |seed| {
+                                FastRng::new_seeded(seed.wrapping_add(i as u64))
+                            });
+                            let mut stored = Vec::new();
+                            for _ in 0 .. per_thread {
+                                let light = &lights[rng.irange(0, lights.len())];
+                                emit(world, light.as_ref(), share, &mut rng, &mut stored);
+                            }
+                            stored
+                        })
+                    })
+                    .collect();
+
+                for worker in workers {
+                    photons.extend(worker.join().unwrap_or_default());
+                }
+            });
+        }
+
+        info!("Stored {} caustic photons", photons.len());
+
+        let mut axes = vec![0; photons.len()];
+        build(&mut photons, &mut axes);
+
+        Self {
+            photons,
+            emitters,
+            axes,
+            radius,
+            neighbours: 64,
+        }
+    }
+
+    /// The number of photons used for a radiance estimate
+    #[must_use]
+    pub const fn neighbours(mut self, neighbours: usize) -> Self {
+        self.neighbours = neighbours;
+        self
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.photons.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.photons.is_empty()
+    }
+
+    /**
+     * Whether the caustics of emitters with this material are in the map,
+     * i.e. photons were stored and the emitter sent photons out.
+     */
+    #[must_use]
+    pub fn holds_caustics_of(&self, material: &Arc<dyn Material>) -> bool {
+        !self.photons.is_empty() && self.emitters.iter().any(|emitter| Arc::ptr_eq(emitter, material))
+    }
+
+    fn nearest(&self, point: &Point3, lo: usize, hi: usize, search: &mut Search) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let photon = &self.photons[mid];
+        let axis = self.axes[mid];
+        let delta = point[axis] - photon.point[axis];
+
+        // the side of the point first, the other side only if it's close enough
+        let (near, far) = if delta < 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.nearest(point, near.0, near.1, search);
+
+        let distance_squared = (point - &photon.point).length_squared();
+        if distance_squared < search.max_squared {
+            search.heap.push(Neighbour { distance_squared, index: mid });
+            if search.heap.len() > self.neighbours {
+                search.heap.pop();
+            }
+            if search.heap.len() == self.neighbours {
+                search.max_squared = search.heap.peek().map_or(search.max_squared, |n| n.distance_squared);
+            }
+        }
+
+        if delta * delta < search.max_squared {
+            self.nearest(point, far.0, far.1, search);
+        }
+    }
+
+    /**
+     * Radiance of the caustics leaving the hit point towards the ray origin.
+     * The reflectance of the surface for each photon is given by the
+     * function, for a photon direction of travel.
+     */
+    pub fn radiance<F>(&self, hit: &HitRecord, reflectance: F) -> Vec3
+        where F: Fn(&Vec3) -> Vec3
+    {
+        let mut search = Search {
+            heap: BinaryHeap::with_capacity(self.neighbours + 1),
+            max_squared: self.radius * self.radius,
+        };
+        self.nearest(&hit.point, 0, self.photons.len(), &mut search);
+
+        if search.heap.is_empty() {
+            return Vec3::default();
+        }
+
+        let mut flux = Vec3::default();
+        for neighbour in &search.heap {
+            let photon = &self.photons[neighbour.index];
+
+            // only photons arriving on the visible side of the surface
+            if photon.direction.dot(&hit.normal) < 0.0 {
+                flux += reflectance(&photon.direction) * &photon.power;
+            }
+        }
+
+        flux / (PI * search.max_squared)
+    }
+}
+
+
+fn build(photons: &mut [Photon], axes: &mut [usize]) {
+    if photons.len() <= 1 {
+        return;
+    }
+
+    let mut min = Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut max = Vec3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for photon in photons.iter() {
+        min = Vec3::new_min(&min, &photon.point);
+        max = Vec3::new_max(&max, &photon.point);
+    }
+
+    let extent = max - min;
+    let split_axis = if extent.x > extent.y && extent.x > extent.z { 0 } else if extent.y > extent.z { 1 } else { 2 };
+
+    let mid = photons.len() / 2;
+    photons.select_nth_unstable_by(mid, |a, b| a.point[split_axis].total_cmp(&b.point[split_axis]));
+    axes[mid] = split_axis;
+
+    let (left, right) = photons.split_at_mut(mid);
+    let (left_axes, right_axes) = axes.split_at_mut(mid);
+    build(left, left_axes);
+    build(&mut right[1 ..], &mut right_axes[1 ..]);
+}
+
+
+// looks at the light from just above the point to find its material
+fn probe(light: &dyn Hittable, point: &Point3, normal: &Vec3) -> Option<HitRecord> {
+    let ray = Ray::new(point + normal * 0.001, -normal, 0.0);
+    light.hit(&ray, &(0.0 .. 0.01))
+}
+
+
+/**
+ * Sends one photon from the light into the scene. The light emits on both
+ * sides of its surface like `DiffuseLight`, with a cosine distribution.
+ */
+fn emit(world: &World, light: &dyn Hittable, share: f64, rng: &mut FastRng, stored: &mut Vec<Photon>) {
+    let Some(sample) = light.sample_surface(rng) else {
+        return;
+    };
+
+    let normal = if rng.gen() < 0.5 { sample.normal } else { -sample.normal };
+
+    let emitted = probe(light, &sample.point, &normal)
+        .and_then(|hit| hit.material.as_ref().and_then(|m| m.emitted(hit.u, hit.v, &hit.point)));
+
+    let Some(emitted) = emitted else {
+        return;
+    };
+
+    // cosine weighted directions carry pi times the radiance per area, for both sides
+    let mut power = emitted * (2.0 * PI * sample.area * share);
+    let direction = ONB::build_from(&normal).local(&Vec3::random_cosine_direction(rng));
+    let mut ray = Ray::new(sample.point.clone(), direction, 0.0);
+    let mut specular = false;
+
+    for bounce in 0 .. 16 {
+        let Some(hit) = world.hit(&ray, &(0.001 .. f64::INFINITY)) else {
+            return;
+        };
+
+        let material = match hit.material {
+            Some(ref m) => m.clone(),
+            None => world.default_material.clone(),
+        };
+
+        let Some(srec) = material.scatter(&ray, &hit) else {
+            return;
+        };
+
+        if !srec.skip_pdf {
+            if specular {
+                stored.push(Photon { point: hit.point, direction: ray.direction, power });
+            }
+            return;
+        }
+
+        let Some(scattered) = srec.ray else {
+            return;
+        };
+
+        power = srec.color * &power;
+        specular = true;
+        ray = scattered;
+
+        if bounce >= 3 {
+            // russian roulette by the loss of power at this bounce
+            let survive = power.x.max(power.y).max(power.z) / (2.0 * PI * sample.area * share);
+            let survive = survive.min(1.0);
+            if survive <= 0.0 || rng.gen() >= survive {
+                return;
+            }
+            power /= survive;
+        }
+    }
+}
@@ -5,6 +5,8 @@ mod ray;
 mod vec3;
 mod onb;
 mod pdf;
+mod spectrum;
+mod polynomial;
 
 use std::ops::{Bound, RangeBounds};
 
@@ -14,7 +16,10 @@ pub use {
     random::{Random, SeedRandom, FastRng},
     ray::Ray,
     onb::ONB,
-    pdf::{PDF, CosinePdf, SpherePdf, HittablePdf, MixturePdf, BlinnPhongPdf, ReflectionPdf, CosinePdfExponent},
+    polynomial::{solve_quadratic, solve_cubic, solve_quartic},
+    pdf::{PDF, CosinePdf, SpherePdf, HittablePdf, MixturePdf, BlinnPhongPdf, ReflectionPdf, CosinePdfExponent, GgxPdf,
+          HenyeyGreensteinPdf, DoubleLobePdf},
+    spectrum::{cie_xyz, xyz_to_rgb, sample_wavelength, wavelength_weight, WAVELENGTH_MIN, WAVELENGTH_MAX},
     std::f64::consts::PI,
     vec3::{Point3, Vec3},
 };
@@ -21,6 +21,14 @@ impl ONB {
             a0.z * a.x + a1.z * a.y + a2.z * a.z)
 
     }
+
+    /**
+     * The inverse of `local()`, expresses a world space vector in this base.
+     */
+    #[must_use]
+    pub fn to_local(&self, a: &Vec3) -> Vec3 {
+        Vec3::new(a.dot(&self.axis[0]), a.dot(&self.axis[1]), a.dot(&self.axis[2]))
+    }
   
     pub fn build_from(n: &Vec3) -> ONB {
         let w = n.unit();
@@ -39,4 +47,26 @@ impl ONB {
             axis: [u, v, w],
         }
     }
+
+    /**
+     * A base with the normal as z axis and the tangent, made orthogonal
+     * to the normal, as x axis. Falls back to `build_from()` if the
+     * tangent is parallel to the normal.
+     */
+    #[must_use]
+    pub fn build_from_tangent(n: &Vec3, t: &Vec3) -> Self {
+        let w = n.unit();
+        let u = t - w.dot(t) * &w;
+
+        if u.length_squared() < 1e-8 {
+            return Self::build_from(n);
+        }
+
+        let u = u.unit();
+        let v = w.cross(&u);
+
+        Self {
+            axis: [u, v, w],
+        }
+    }
 }
\ No newline at end of file
@@ -210,6 +210,186 @@ impl PDF for BlinnPhongPdf {
 }
 
 
+/**
+ * GGX (Trowbridge-Reitz) microfacet distribution. Directions are generated by
+ * sampling the distribution of normals which are visible from the outgoing
+ * direction and reflecting the outgoing direction at the sampled normal.
+ * All vectors in the microfacet functions are in the local frame of the
+ * surface, where the normal is the z axis. Anisotropic distributions have
+ * separate widths along the tangent (x axis) and the bitangent (y axis).
+ */
+#[derive(Debug)]
+pub struct GgxPdf {
+    onb: ONB,
+    wo: Vec3,
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl GgxPdf {
+
+    /**
+     * The outgoing direction points away from the surface, i.e. it is the
+     * reversed incoming ray direction. The roughness is in range 0 .. 1, the
+     * distribution width alpha is the roughness squared.
+     */
+    #[must_use]
+    pub fn new(normal: &Vec3, wo: &Vec3, roughness: f64) -> Self {
+        Self::with_onb(ONB::build_from(normal), wo, roughness, roughness)
+    }
+
+    /**
+     * An anisotropic distribution, with the roughness along the tangent
+     * and the roughness along the bitangent of the surface.
+     */
+    #[must_use]
+    pub fn anisotropic(normal: &Vec3, tangent: &Vec3, wo: &Vec3, roughness_u: f64, roughness_v: f64) -> Self {
+        Self::with_onb(ONB::build_from_tangent(normal, tangent), wo, roughness_u, roughness_v)
+    }
+
+    fn with_onb(onb: ONB, wo: &Vec3, roughness_u: f64, roughness_v: f64) -> Self {
+
+        assert!((wo.length_squared() - 1.0).abs() < 0.00001);
+
+        let wo = onb.to_local(wo);
+
+        Self {
+            onb,
+            wo,
+            alpha_x: (roughness_u * roughness_u).max(0.0001),
+            alpha_y: (roughness_v * roughness_v).max(0.0001),
+        }
+    }
+
+    #[must_use]
+    pub const fn wo(&self) -> &Vec3 {
+        &self.wo
+    }
+
+    #[must_use]
+    pub fn to_local(&self, v: &Vec3) -> Vec3 {
+        self.onb.to_local(v)
+    }
+
+    #[must_use]
+    pub fn to_world(&self, v: &Vec3) -> Vec3 {
+        self.onb.local(v)
+    }
+
+    // density of microfacet normals
+    #[must_use]
+    pub fn distribution(&self, m: &Vec3) -> f64 {
+        if m.z <= 0.0 {
+            return 0.0;
+        }
+
+        let ax = self.alpha_x;
+        let ay = self.alpha_y;
+        let t = m.z.mul_add(m.z, (m.x * m.x) / (ax * ax) + (m.y * m.y) / (ay * ay));
+
+        1.0 / (PI * ax * ay * t * t)
+    }
+
+    // Smith's auxiliary function, the ratio of hidden to visible microfacet area
+    #[must_use]
+    pub fn lambda(&self, v: &Vec3) -> f64 {
+        let cos2 = v.z * v.z;
+        if cos2 <= 0.0 {
+            return 0.0;
+        }
+
+        let ax = self.alpha_x * v.x;
+        let ay = self.alpha_y * v.y;
+        ((1.0 + ax.mul_add(ax, ay * ay) / cos2).sqrt() - 1.0) * 0.5
+    }
+
+    // fraction of microfacets visible from direction v
+    #[must_use]
+    pub fn g1(&self, v: &Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(v))
+    }
+
+    // height correlated masking and shadowing
+    #[must_use]
+    pub fn g2(&self, wo: &Vec3, wi: &Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /**
+     * Samples a microfacet normal which is visible from the outgoing
+     * direction, following E. Heitz, "Sampling the GGX Distribution of
+     * Visible Normals", JCGT 2018.
+     */
+    pub fn sample_normal(&self, rng: &mut FastRng) -> Vec3 {
+        let wo = if self.wo.z < 0.0 { -self.wo.clone() } else { self.wo.clone() };
+
+        // stretch the view direction to the hemisphere configuration
+        let vh = Vec3::new(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).unit();
+
+        let lensq = vh.x.mul_add(vh.x, vh.y * vh.y);
+        let t1 = if lensq > 0.0 {
+            Vec3::new(-vh.y, vh.x, 0.0) / lensq.sqrt()
+        }
+        else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(&t1);
+
+        // point on the projected disk
+        let r = rng.gen().sqrt();
+        let phi = 2.0 * PI * rng.gen();
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s).mul_add((1.0 - p1 * p1).sqrt(), s * r * phi.sin());
+
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        // unstretch
+        Vec3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(0.0)).unit()
+    }
+
+    // density of sampling the microfacet normal m with sample_normal()
+    #[must_use]
+    pub fn normal_pdf(&self, m: &Vec3) -> f64 {
+        let cos_o = self.wo.z.abs();
+        if cos_o <= 0.0 {
+            return 0.0;
+        }
+
+        self.g1(&self.wo) * self.wo.dot(m).abs() * self.distribution(m) / cos_o
+    }
+}
+
+impl PDF for GgxPdf {
+
+    fn value(&self, direction: &Vec3) -> f64 {
+
+        assert!((direction.length_squared() - 1.0).abs() < 0.00001);
+
+        let wi = self.onb.to_local(direction);
+        if wi.z <= 0.0 || self.wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        let m = (&self.wo + &wi).unit();
+        let cos_om = self.wo.dot(&m);
+        if cos_om <= 0.0 {
+            return 0.0;
+        }
+
+        // jacobian of the reflection at the microfacet
+        self.normal_pdf(&m) / (4.0 * cos_om)
+    }
+  
+    fn generate(&self, rng: &mut FastRng) -> Vec3 {
+        let m = self.sample_normal(rng);
+        let wi = 2.0 * self.wo.dot(&m) * &m - &self.wo;
+
+        self.onb.local(&wi)
+    }
+}
+
+
 #[derive(Debug)]
 pub struct SpherePdf {
 }
@@ -233,19 +413,115 @@ impl PDF for SpherePdf {
 }
 
 
+/**
+ * The Henyey-Greenstein phase function of participating media, relative to
+ * the direction the light travels. Positive g scatters light forward,
+ * negative g backward and g = 0 scatters uniformly into all directions.
+ */
+#[derive(Debug)]
+pub struct HenyeyGreensteinPdf {
+    onb: ONB,
+    g: f64,
+}
+
+impl HenyeyGreensteinPdf {
+    #[must_use]
+    pub fn new(direction: &Vec3, g: f64) -> Self {
+        Self {
+            onb: ONB::build_from(direction),
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+
+    /// The phase function for the cosine between the light and the scattered direction
+    #[must_use]
+    pub fn phase(&self, cos_theta: f64) -> f64 {
+        let g2 = self.g * self.g;
+        let denom = (2.0 * self.g).mul_add(-cos_theta, 1.0 + g2);
+
+        (1.0 - g2) / (4.0 * PI * denom * denom.sqrt())
+    }
+}
+
+impl PDF for HenyeyGreensteinPdf {
+
+    fn value(&self, direction: &Vec3) -> f64 {
+        self.phase(direction.unit().dot(&self.onb.axis[2]))
+    }
+
+    fn generate(&self, rng: &mut FastRng) -> Vec3 {
+        let g = self.g;
+        let r1 = rng.gen();
+        let r2 = rng.gen();
+
+        let cos_theta = if g.abs() < 0.001 {
+            2.0f64.mul_add(-r1, 1.0)
+        }
+        else {
+            let sq = g.mul_add(-g, 1.0) / (2.0 * g).mul_add(-r1, 1.0 + g);
+            ((g.mul_add(g, 1.0) - sq * sq) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * r2;
+
+        self.onb.local(&Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta))
+    }
+}
+
+
+/**
+ * Blend of a forward and a backward Henyey-Greenstein lobe, which fits
+ * clouds and smoke better than a single lobe. The weight belongs to the
+ * first lobe.
+ */
+#[derive(Debug)]
+pub struct DoubleLobePdf {
+    first: HenyeyGreensteinPdf,
+    second: HenyeyGreensteinPdf,
+    weight: f64,
+}
+
+impl DoubleLobePdf {
+    #[must_use]
+    pub fn new(direction: &Vec3, g1: f64, g2: f64, weight: f64) -> Self {
+        Self {
+            first: HenyeyGreensteinPdf::new(direction, g1),
+            second: HenyeyGreensteinPdf::new(direction, g2),
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl PDF for DoubleLobePdf {
+
+    fn value(&self, direction: &Vec3) -> f64 {
+        self.weight.mul_add(self.first.value(direction), (1.0 - self.weight) * self.second.value(direction))
+    }
+
+    fn generate(&self, rng: &mut FastRng) -> Vec3 {
+        if rng.gen() < self.weight {
+            self.first.generate(rng)
+        }
+        else {
+            self.second.generate(rng)
+        }
+    }
+}
+
+
 #[derive(Debug)]
 pub struct HittablePdf<'a> {
     objects: &'a HittableList,
     origin: Point3,
-    normal: Vec3,
 }
 
 impl HittablePdf <'_> {
-    pub fn new<'a>(objects: &'a HittableList, origin: &'a Point3, normal: &'a Vec3) -> HittablePdf<'a> { 
+    #[must_use]
+    pub fn new<'a>(objects: &'a HittableList, origin: &Point3) -> HittablePdf<'a> { 
         HittablePdf {
-            objects: objects,
+            objects,
             origin: origin.clone(),
-            normal: normal.clone(),
         }
     }
 }
@@ -254,12 +530,7 @@ impl HittablePdf <'_> {
 impl PDF for HittablePdf<'_> {
   
     fn value(&self, direction: &Vec3) -> f64 {
-        /*
         self.objects.pdf_value(&self.origin, direction)
-        */
-
-        let pdf = CosinePdf::new(&self.normal);
-        return pdf.value(direction);
     }
   
     fn generate(&self, rng: &mut FastRng) -> Vec3 {
@@ -268,40 +539,55 @@ impl PDF for HittablePdf<'_> {
 }
 
 
+/**
+ * Samples `p0` with probability `weight` and `p1` otherwise. The density of
+ * a direction is the weighted sum of both densities, which makes this a
+ * one-sample multiple importance sampler using the balance heuristic.
+ */
 pub struct MixturePdf<'a> {
-    p0: Box<&'a dyn PDF>, 
-    p1: Box<&'a dyn PDF>, 
+    p0: &'a dyn PDF, 
+    p1: &'a dyn PDF, 
+    weight: f64,
 }
 
 
 impl <'a> MixturePdf<'a> {
     pub fn new(p0: &'a dyn PDF, p1: &'a dyn PDF) -> MixturePdf<'a> {
+        Self::with_weight(p0, p1, 0.5)
+    }
+
+    /// # Panics
+    /// When the weight of `p0` is not between 0 and 1
+    pub fn with_weight(p0: &'a dyn PDF, p1: &'a dyn PDF, weight: f64) -> Self {
+        assert!((0.0..=1.0).contains(&weight));
+
         MixturePdf {
-            p0: Box::new(p0),
-            p1: Box::new(p1),
+            p0,
+            p1,
+            weight,
         }
     }
 }
 
 impl Debug for MixturePdf<'_> {
-    fn fmt(&self, _: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        Ok(())
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.write_fmt(format_args!("MixturePdf {{ weight: {} }}", self.weight))
     }
 }
 
 impl PDF for MixturePdf<'_> {
 
     fn value(&self, direction: &Vec3) -> f64 {
-        return 0.5 * self.p0.value(direction) + 0.5 * self.p1.value(direction);
+        self.weight.mul_add(self.p0.value(direction), (1.0 - self.weight) * self.p1.value(direction))
     }
   
     fn generate(&self, rng: &mut FastRng) -> Vec3 {
 
-        if rng.gen() < 0.5 {
-            return self.p0.generate(rng);
+        if rng.gen() < self.weight {
+            self.p0.generate(rng)
         }
         else {
-            return self.p1.generate(rng);
+            self.p1.generate(rng)
         }
     }  
 }
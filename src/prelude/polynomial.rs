@@ -0,0 +1,226 @@
+// Real roots of low order polynomials, for objects which are hit where
+// a polynomial in the ray parameter becomes zero
+
+use std::f64::consts::PI;
+
+
+/**
+ * Real roots of a x^2 + b x + c = 0 in ascending order. The roots are
+ * computed without subtracting nearly equal numbers, so both are accurate
+ * even if b^2 is much larger than 4ac.
+ */
+#[must_use]
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return if b == 0.0 { Vec::new() } else { vec![-c / b] };
+    }
+
+    let discriminant = b.mul_add(b, -(4.0 * a * c));
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    let q = -0.5 * b.signum().mul_add(discriminant.sqrt(), b);
+    if q == 0.0 {
+        // b and c are both zero
+        return vec![0.0, 0.0];
+    }
+
+    let (r1, r2) = (q / a, c / q);
+    if r1 < r2 { vec![r1, r2] } else { vec![r2, r1] }
+}
+
+
+/**
+ * Real roots of x^3 + a x^2 + b x + c = 0 in ascending order, after
+ * Cardano if there is one real root and by the trigonometric method if
+ * there are three.
+ */
+#[must_use]
+#[allow(clippy::many_single_char_names)]
+#[allow(clippy::suboptimal_flops)] // the closed form as usually written
+pub fn solve_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // substitute x = t - a/3 to get t^3 + p t + q = 0
+    let shift = a / 3.0;
+    let p = b - a * shift;
+    let q = c - b * shift + 2.0 * shift * shift * shift;
+
+    let half_q = q * 0.5;
+    let third_p = p / 3.0;
+    let discriminant = half_q * half_q + third_p * third_p * third_p;
+
+    let mut roots = if discriminant > 0.0 {
+        let sqrt = discriminant.sqrt();
+        vec![(-half_q + sqrt).cbrt() + (-half_q - sqrt).cbrt() - shift]
+    }
+    else if p == 0.0 {
+        vec![-shift]
+    }
+    else {
+        let r = (-third_p).sqrt();
+        let phi = (-half_q / (r * r * r)).clamp(-1.0, 1.0).acos() / 3.0;
+        (0 .. 3)
+            .map(|k| 2.0 * r * (phi - 2.0 * PI * f64::from(k) / 3.0).cos() - shift)
+            .collect()
+    };
+
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+
+/**
+ * Real roots of x^4 + a x^3 + b x^2 + c x + d = 0 in ascending order, after
+ * Ferrari. The closed form loses precision if the roots are far apart, so
+ * each root is refined by a few Newton steps on the original polynomial.
+ */
+#[must_use]
+#[allow(clippy::many_single_char_names)]
+#[allow(clippy::suboptimal_flops)] // the closed form as usually written
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // substitute x = y - a/4 to get y^4 + p y^2 + q y + r = 0
+    let shift = a * 0.25;
+    let a2 = a * a;
+    let p = b - 0.375 * a2;
+    let q = c - 0.5 * a * b + 0.125 * a2 * a;
+    let r = d - 0.25 * a * c + 0.0625 * a2 * b - 3.0 / 256.0 * a2 * a2;
+
+    let mut roots = Vec::with_capacity(4);
+
+    if q.abs() < 1e-12 {
+        // biquadratic, y^2 solves a quadratic equation
+        for z in solve_quadratic(1.0, p, r) {
+            if z >= 0.0 {
+                let y = z.sqrt();
+                roots.push(y - shift);
+                roots.push(-y - shift);
+            }
+        }
+    }
+    else {
+        // with m from the resolvent cubic both sides of
+        // (y^2 + p/2 + m)^2 = 2m (y - q/4m)^2 are squares
+        let m = solve_cubic(p, 0.25 * p * p - r, -0.125 * q * q)
+            .into_iter()
+            .fold(0.0, f64::max);
+
+        if m > 0.0 {
+            let s = (2.0 * m).sqrt();
+            let base = 0.5 * p + m;
+            let offset = q / (2.0 * s);
+
+            roots.extend(solve_quadratic(1.0, -s, base + offset).into_iter().map(|y| y - shift));
+            roots.extend(solve_quadratic(1.0, s, base - offset).into_iter().map(|y| y - shift));
+        }
+    }
+
+    let value = |x: f64| (x + a).mul_add(x, b).mul_add(x, c).mul_add(x, d);
+    let slope = |x: f64| 4.0f64.mul_add(x, 3.0 * a).mul_add(x, 2.0 * b).mul_add(x, c);
+
+    for root in &mut roots {
+        for _ in 0 .. 3 {
+            let step = value(*root) / slope(*root);
+            // near double roots the slope vanishes, keep the better estimate
+            if !step.is_finite() || value(*root - step).abs() >= value(*root).abs() {
+                break;
+            }
+            *root -= step;
+        }
+    }
+
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // coefficients of the monic polynomial with the given roots, highest power first
+    fn from_roots(roots: &[f64]) -> Vec<f64> {
+        let mut coefficients = vec![1.0];
+        for root in roots {
+            let mut next = coefficients.clone();
+            next.push(0.0);
+            for (i, c) in coefficients.iter().enumerate() {
+                next[i + 1] -= c * root;
+            }
+            coefficients = next;
+        }
+        coefficients
+    }
+
+    fn assert_roots(found: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(found.len(), expected.len(), "roots {:?}, expected {:?}", found, expected);
+        for (f, e) in found.iter().zip(expected) {
+            assert!((f - e).abs() <= tolerance * e.abs().max(1.0), "roots {:?}, expected {:?}", found, expected);
+        }
+    }
+
+    #[test]
+    fn quadratic() {
+        assert_roots(&solve_quadratic(1.0, -4.0, 3.0), &[1.0, 3.0], 1e-12);
+        assert_roots(&solve_quadratic(2.0, 0.0, -8.0), &[-2.0, 2.0], 1e-12);
+        assert!(solve_quadratic(1.0, 0.0, 1.0).is_empty());
+
+        // the small root must not cancel out
+        assert_roots(&solve_quadratic(1.0, -1e8, 1.0), &[1e-8, 1e8], 1e-12);
+    }
+
+    #[test]
+    fn cubic() {
+        let c = from_roots(&[1.0, 2.0, 3.0]);
+        assert_roots(&solve_cubic(c[1], c[2], c[3]), &[1.0, 2.0, 3.0], 1e-9);
+
+        // (x - 2)(x^2 + 1) has one real root
+        assert_roots(&solve_cubic(-2.0, 1.0, -2.0), &[2.0], 1e-9);
+    }
+
+    #[test]
+    fn quartic() {
+        let c = from_roots(&[-1.5, 0.5, 2.0, 4.0]);
+        assert_roots(&solve_quartic(c[1], c[2], c[3], c[4]), &[-1.5, 0.5, 2.0, 4.0], 1e-9);
+
+        // (x^2 + 1)(x^2 + 4) has no real roots
+        assert!(solve_quartic(0.0, 5.0, 0.0, 4.0).is_empty());
+    }
+
+    #[test]
+    fn quartic_double_root() {
+        let c = from_roots(&[1.0, 1.0, 3.0, 4.0]);
+        let roots = solve_quartic(c[1], c[2], c[3], c[4]);
+
+        // the double root may be found once per side or not split at all
+        for root in &roots {
+            assert!([1.0, 3.0, 4.0].iter().any(|e| (root - e).abs() < 1e-6), "roots {:?}", roots);
+        }
+        for expected in [1.0, 3.0, 4.0] {
+            assert!(roots.iter().any(|root| (root - expected).abs() < 1e-6), "roots {:?}", roots);
+        }
+    }
+
+    #[test]
+    fn quartic_biquadratic() {
+        // no odd powers, so y^2 solves a quadratic equation
+        assert_roots(&solve_quartic(0.0, -5.0, 0.0, 4.0), &[-2.0, -1.0, 1.0, 2.0], 1e-12);
+
+        // roots symmetric around 2, the linear term vanishes after the substitution
+        let c = from_roots(&[0.0, 1.0, 3.0, 4.0]);
+        assert_roots(&solve_quartic(c[1], c[2], c[3], c[4]), &[0.0, 1.0, 3.0, 4.0], 1e-9);
+    }
+
+    #[test]
+    fn quartic_far_away() {
+        // a ray from far away through a torus, the hits are close
+        // together compared to their distance
+        let expected = [1000.0, 1000.5, 1001.5, 1002.0];
+        let c = from_roots(&expected);
+        assert_roots(&solve_quartic(c[1], c[2], c[3], c[4]), &expected, 1e-9);
+
+        // hits at very different distances
+        let expected = [0.01, 0.02, 50.0, 80.0];
+        let c = from_roots(&expected);
+        assert_roots(&solve_quartic(c[1], c[2], c[3], c[4]), &expected, 1e-9);
+    }
+}
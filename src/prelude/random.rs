@@ -8,6 +8,8 @@ use {
 
 use rand_xorshift::XorShiftRng;
 
+use crate::prelude::Ray;
+
 
 fn gen<R: Rng>(mut rng: R) -> f64 {
     rng.gen()
@@ -105,6 +107,26 @@ impl SeedRandom {
 }
 
 
+// splitmix64 finalizer, spreads the bits of the input over the whole output
+const fn mix(z: u64) -> u64 {
+    let z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+
+fn hash_ray(ray: &Ray) -> u64 {
+    let values = [
+        ray.origin.x, ray.origin.y, ray.origin.z,
+        ray.direction.x, ray.direction.y, ray.direction.z,
+        ray.departure_time,
+    ];
+
+    values.iter().fold(0, |hash, v| mix(hash ^ v.to_bits()))
+}
+
+
 #[derive(Debug)]
 pub struct FastRng {
     rng: XorShiftRng,
@@ -121,6 +143,42 @@ impl FastRng {
     }
 
 
+    #[must_use]
+    pub fn new_seeded(seed: u64) -> Self {
+        Self {
+            rng: XorShiftRng::seed_from_u64(seed)
+        }
+    }
+
+    /**
+     * A generator seeded by the ray, for code which has no access to the
+     * render's generator, e.g. `Hittable::hit`. The results are as
+     * deterministic as the ray itself.
+     */
+    #[must_use]
+    pub fn for_ray(ray: &Ray) -> Self {
+        Self::new_seeded(hash_ray(ray))
+    }
+
+    /**
+     * Like `for_ray()`, but different for each hit along the ray.
+     */
+    #[must_use]
+    pub fn for_hit(ray: &Ray, t: f64) -> Self {
+        Self::new_seeded(mix(hash_ray(ray) ^ t.to_bits()))
+    }
+
+    /**
+     * Like `for_hit()`, but a different sequence for each salt. Materials
+     * which wrap another material use it for their own choices, which would
+     * be correlated with the choices of the wrapped material otherwise.
+     */
+    #[must_use]
+    pub fn for_hit_salted(ray: &Ray, t: f64, salt: u64) -> Self {
+        Self::new_seeded(mix(mix(hash_ray(ray) ^ t.to_bits()) ^ salt))
+    }
+
+
     #[inline]
     pub fn gen(&mut self) -> f64 {
         self.rng.next_u64() as f64 / u64::MAX as f64
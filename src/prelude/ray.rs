@@ -5,6 +5,8 @@ pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub departure_time: f64,
+    // in nanometers, only set in spectral rendering mode
+    pub wavelength: Option<f64>,
 }
 
 impl Ray {
@@ -14,9 +16,16 @@ impl Ray {
             origin,
             direction,
             departure_time,
+            wavelength: None,
         }
     }
 
+    #[must_use]
+    pub const fn with_wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
     #[must_use]
     pub fn at(&self, t: f64) -> Vec3 {
         // &self.origin + &self.direction * t
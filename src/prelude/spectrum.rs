@@ -0,0 +1,87 @@
+use crate::prelude::{FastRng, Vec3};
+
+use std::sync::OnceLock;
+
+
+// range of visible wavelengths in nanometers
+pub const WAVELENGTH_MIN: f64 = 380.0;
+pub const WAVELENGTH_MAX: f64 = 780.0;
+
+
+// piecewise gaussian with different widths left and right of the center
+fn lobe(x: f64, mu: f64, sigma_left: f64, sigma_right: f64) -> f64 {
+    let t = (x - mu) / if x < mu { sigma_left } else { sigma_right };
+    (-0.5 * t * t).exp()
+}
+
+
+/**
+ * The CIE 1931 color matching functions, using the analytic multi-lobe fit
+ * from Wyman, Sloan and Shirley, "Simple Analytic Approximations to the
+ * CIE XYZ Color Matching Functions", JCGT 2013.
+ */
+#[must_use]
+#[allow(clippy::suboptimal_flops)] // the coefficients as published
+pub fn cie_xyz(wavelength: f64) -> Vec3 {
+    let l = wavelength;
+
+    let x = 1.056 * lobe(l, 599.8, 37.9, 31.0)
+          + 0.362 * lobe(l, 442.0, 16.0, 26.7)
+          - 0.065 * lobe(l, 501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(l, 568.8, 46.9, 40.5)
+          + 0.286 * lobe(l, 530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(l, 437.0, 11.8, 36.0)
+          + 0.681 * lobe(l, 459.0, 26.0, 13.8);
+
+    Vec3::new(x, y, z)
+}
+
+
+// linear sRGB with D65 white point
+#[must_use]
+#[allow(clippy::suboptimal_flops)] // the coefficients as published
+pub fn xyz_to_rgb(xyz: &Vec3) -> Vec3 {
+    Vec3::new(
+         3.2406 * xyz.x - 1.5372 * xyz.y - 0.4986 * xyz.z,
+        -0.9689 * xyz.x + 1.8758 * xyz.y + 0.0415 * xyz.z,
+         0.0557 * xyz.x - 0.2040 * xyz.y + 1.0570 * xyz.z)
+}
+
+
+// mean RGB response over the visible range
+fn mean_rgb() -> &'static Vec3 {
+    static MEAN: OnceLock<Vec3> = OnceLock::new();
+
+    MEAN.get_or_init(|| {
+        let steps = 1000;
+        let mut sum = Vec3::default();
+
+        for i in 0 .. steps {
+            let l = WAVELENGTH_MIN + (WAVELENGTH_MAX - WAVELENGTH_MIN) * (f64::from(i) + 0.5) / f64::from(steps);
+            sum += xyz_to_rgb(&cie_xyz(l));
+        }
+
+        sum / f64::from(steps)
+    })
+}
+
+
+/// A wavelength uniformly sampled from the visible range
+pub fn sample_wavelength(rng: &mut FastRng) -> f64 {
+    rng.range(WAVELENGTH_MIN, WAVELENGTH_MAX)
+}
+
+
+/**
+ * The weight which turns the RGB radiance carried by a path of the given
+ * wavelength into its contribution to the pixel. The weights of uniformly
+ * sampled wavelengths average to white, so paths without wavelength
+ * dependent effects keep their color on average.
+ */
+#[must_use]
+pub fn wavelength_weight(wavelength: f64) -> Vec3 {
+    let rgb = xyz_to_rgb(&cie_xyz(wavelength));
+    let mean = mean_rgb();
+
+    Vec3::new(rgb.x / mean.x, rgb.y / mean.y, rgb.z / mean.z)
+}
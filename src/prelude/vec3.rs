@@ -126,6 +126,25 @@ impl Vec3 {
     }
 
 
+    /**
+     * Random direction (around the z axis) inside the cone that a sphere of
+     * the given radius covers when seen from the given squared distance.
+     */
+    #[inline]
+    pub fn random_to_sphere(radius: f64, distance_squared: f64, rng: &mut FastRng) -> Self {
+        let r1 = rng.gen();
+        let r2 = rng.gen();
+        let z = r2.mul_add((1.0 - radius * radius / distance_squared).sqrt() - 1.0, 1.0);
+
+        let phi = 2.0 * PI * r1;
+        let q = (1.0 - z * z).sqrt();
+        let x = phi.cos() * q;
+        let y = phi.sin() * q;
+
+        Self::new(x, y, z)
+    }
+
+
     #[must_use]
     pub fn random_unit_dir(dir: &Self, rng: &mut FastRng) -> Self {
         let u = Self::random_unit(rng);
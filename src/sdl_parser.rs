@@ -4,19 +4,29 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::fmt::Debug;
+use log::warn;
 
 use crate::prelude::Vec3;
+use crate::prelude::Point3;
 use crate::prelude::Color;
 use crate::prelude::PI;
+use crate::prelude::FastRng;
 
 use crate::hittable::Hittable;
 use crate::hittable::transform::Transform;
 use crate::hittable::transform::TransformStack;
 use crate::hittable::transform::TfFacade;
+use crate::hittable::transform::NormalFacade;
 use crate::hittable::Sphere;
 use crate::hittable::Box as GeometryBox;
 use crate::hittable::geometry::Quadric;
+use crate::hittable::geometry::Torus;
+use crate::hittable::geometry::Cone;
+use crate::hittable::geometry::Cylinder;
+use crate::hittable::geometry::Disc;
+use crate::hittable::geometry::Plane;
 use crate::hittable::collection::HittableList;
+use crate::hittable::medium::HeterogeneousMedium;
 use crate::hittable::csg::Difference;
 use crate::hittable::Intersection;
 
@@ -25,11 +35,26 @@ use crate::material::CommonMaterialSettings;
 use crate::material::Lambertian;
 use crate::material::Metal;
 use crate::material::DiffuseMetal;
-use crate::material::MixedMaterial;
+use crate::material::Principled;
+use crate::material::Dielectric;
+use crate::material::Glass;
+use crate::material::Dispersion;
+use crate::material::GgxConductor;
+use crate::material::GgxDielectric;
+use crate::material::PhaseFunction;
+use crate::material::Subsurface;
+use crate::material::DiffuseLight;
 
 use crate::texture::Checker;
+use crate::texture::Perlin;
+use crate::texture::Image;
+use crate::texture::NormalModifier;
+use crate::texture::BumpMap;
 use crate::texture::Texture;
 
+use crate::light::EnvironmentLight;
+use crate::light::PhysicalSky;
+
 
 // All data parsed from the scene definition
 #[derive(Debug)]
@@ -37,6 +62,14 @@ pub struct SceneData {
     pub camera: Option <CameraData>,
     pub hittables: HittableList,
     pub lights: Vec<LightData>,
+    // a constant background color
+    pub background: Option<Color>,
+    // an environment map, which lights the scene and replaces the background
+    pub environment: Option<Arc<EnvironmentLight>>,
+    // a daylight sky with its sun
+    pub sky: Option<PhysicalSky>,
+    // caustics photon map settings from the global settings
+    pub photons: Option<PhotonData>,
 }
 
 impl SceneData {
@@ -45,6 +78,10 @@ impl SceneData {
             camera: None,
             hittables: HittableList::default(),
             lights: Vec::new(),
+            background: None,
+            environment: None,
+            sky: None,
+            photons: None,
         }        
     }
 }
@@ -56,12 +93,52 @@ pub struct CameraData {
     pub fov_angle: f64,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct PhotonData {
+    // number of photons to emit
+    pub count: usize,
+    // largest gathering radius, derived from the scene size if not given
+    pub radius: Option<f64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LightKind {
+    // a glowing sphere, which gives soft shadows
+    Sphere,
+    Point,
+    Parallel { point_at: Vec3 },
+    Spot { point_at: Vec3, radius: f64, falloff: f64, tightness: f64 },
+}
+
 #[derive(Debug, PartialEq)]
 pub struct LightData {
     pub location: Vec3, 
     pub color: Color, 
+    pub kind: LightKind,
+    pub fade_distance: f64,
+    pub fade_power: f64,
+}
+
+struct MediaData {
+    color: Color,
+    phase: PhaseFunction,
+    max_density: f64,
+    density: Arc<dyn Texture>,
 }
 
+struct InteriorData {
+    ior: f64,
+    fade_distance: f64,
+    fade_power: f64,
+    fade_color: Color,
+    absorption: Option<Vec3>,
+    dispersion: f64,
+    mean_free_path: Option<f64>,
+}
+
+// the material and the normal modifier of a texture block
+type TextureData = (Option<Arc<dyn Material>>, Option<Arc<dyn NormalModifier>>);
+
 #[derive(Debug)]
 struct Token {
     text: String,
@@ -71,6 +148,10 @@ struct Token {
 enum DeclaredEntity {
     Light(LightData),
     Camera(CameraData),
+    Background(Color),
+    Environment(Arc<EnvironmentLight>),
+    Sky(PhysicalSky),
+    Photons(PhotonData),
     Hittable(Arc<dyn Hittable>),
     Directive(String),
     Float(f64),
@@ -129,8 +210,24 @@ enum Symbol {
     Sphere,
     Box,
     Quadric,
+    Torus,
+    Cylinder,
+    Cone,
+    Disc,
+    Plane,
+    Open,
     Light,
 
+    Point,
+    Parallel,
+    Spotlight,
+    PointAt,
+    Radius,
+    Falloff,
+    Tightness,
+    FadeDistance,
+    FadePower,
+
     Intersection,
     Difference,
     Object,
@@ -163,10 +260,52 @@ enum Symbol {
     Reflection,
     Color,
     Rgb,
+    Filter,
+    Transmit,
     Angle,
     Diffuse,
     Phong,
     PhongSize,
+    Roughness,
+    Specular,
+    Sheen,
+    Clearcoat,
+    Transmission,
+    Dielectric,
+    Ior,
+    Interior,
+    Media,
+    Scattering,
+    Density,
+    Bozo,
+    Eccentricity,
+    Subsurface,
+    MeanFreePath,
+    Normal,
+    Bumps,
+    BumpMapBlock,
+    BumpSize,
+    Png,
+    Str,
+    Emission,
+    Nits,
+    ImageMap,
+    FadeColor,
+    Absorption,
+    Dispersion,
+    Background,
+    SkySphere,
+    Hdr,
+    Exr,
+    MapType,
+    Intensity,
+    Sky,
+    Sun,
+    Turbidity,
+    GroundAlbedo,
+    GlobalSettings,
+    Photons,
+    Count,
 
     Checker,
     
@@ -224,8 +363,24 @@ fn build_symbol_map() -> HashMap<String, Symbol> {
     map.insert("sphere".to_string(), Symbol::Sphere);
     map.insert("box".to_string(), Symbol::Box);
     map.insert("quadric".to_string(), Symbol::Quadric);
+    map.insert("torus".to_string(), Symbol::Torus);
+    map.insert("cylinder".to_string(), Symbol::Cylinder);
+    map.insert("cone".to_string(), Symbol::Cone);
+    map.insert("disc".to_string(), Symbol::Disc);
+    map.insert("plane".to_string(), Symbol::Plane);
+    map.insert("open".to_string(), Symbol::Open);
     map.insert("light".to_string(), Symbol::Light);
 
+    map.insert("point".to_string(), Symbol::Point);
+    map.insert("parallel".to_string(), Symbol::Parallel);
+    map.insert("spotlight".to_string(), Symbol::Spotlight);
+    map.insert("point_at".to_string(), Symbol::PointAt);
+    map.insert("radius".to_string(), Symbol::Radius);
+    map.insert("falloff".to_string(), Symbol::Falloff);
+    map.insert("tightness".to_string(), Symbol::Tightness);
+    map.insert("fade_distance".to_string(), Symbol::FadeDistance);
+    map.insert("fade_power".to_string(), Symbol::FadePower);
+
     map.insert("texture".to_string(), Symbol::Texture);
     map.insert("pigment".to_string(), Symbol::Pigment);
     map.insert("finish".to_string(), Symbol::Finish);
@@ -235,11 +390,52 @@ fn build_symbol_map() -> HashMap<String, Symbol> {
     map.insert("metallic".to_string(), Symbol::Metallic);
     map.insert("color".to_string(), Symbol::Color);
     map.insert("rgb".to_string(), Symbol::Rgb);
+    map.insert("filter".to_string(), Symbol::Filter);
+    map.insert("transmit".to_string(), Symbol::Transmit);
     map.insert("checker".to_string(), Symbol::Checker);
     map.insert("angle".to_string(), Symbol::Angle);
     map.insert("diffuse".to_string(), Symbol::Diffuse);
     map.insert("phong".to_string(), Symbol::Phong);
     map.insert("phong_size".to_string(), Symbol::PhongSize);
+    map.insert("roughness".to_string(), Symbol::Roughness);
+    map.insert("specular".to_string(), Symbol::Specular);
+    map.insert("sheen".to_string(), Symbol::Sheen);
+    map.insert("clearcoat".to_string(), Symbol::Clearcoat);
+    map.insert("transmission".to_string(), Symbol::Transmission);
+    map.insert("dielectric".to_string(), Symbol::Dielectric);
+    map.insert("ior".to_string(), Symbol::Ior);
+    map.insert("interior".to_string(), Symbol::Interior);
+    map.insert("media".to_string(), Symbol::Media);
+    map.insert("scattering".to_string(), Symbol::Scattering);
+    map.insert("density".to_string(), Symbol::Density);
+    map.insert("bozo".to_string(), Symbol::Bozo);
+    map.insert("eccentricity".to_string(), Symbol::Eccentricity);
+    map.insert("subsurface".to_string(), Symbol::Subsurface);
+    map.insert("mean_free_path".to_string(), Symbol::MeanFreePath);
+    map.insert("normal".to_string(), Symbol::Normal);
+    map.insert("bumps".to_string(), Symbol::Bumps);
+    map.insert("bump_map".to_string(), Symbol::BumpMapBlock);
+    map.insert("bump_size".to_string(), Symbol::BumpSize);
+    map.insert("png".to_string(), Symbol::Png);
+    map.insert("emission".to_string(), Symbol::Emission);
+    map.insert("nits".to_string(), Symbol::Nits);
+    map.insert("image_map".to_string(), Symbol::ImageMap);
+    map.insert("fade_color".to_string(), Symbol::FadeColor);
+    map.insert("absorption".to_string(), Symbol::Absorption);
+    map.insert("dispersion".to_string(), Symbol::Dispersion);
+    map.insert("background".to_string(), Symbol::Background);
+    map.insert("sky_sphere".to_string(), Symbol::SkySphere);
+    map.insert("hdr".to_string(), Symbol::Hdr);
+    map.insert("exr".to_string(), Symbol::Exr);
+    map.insert("map_type".to_string(), Symbol::MapType);
+    map.insert("intensity".to_string(), Symbol::Intensity);
+    map.insert("sky".to_string(), Symbol::Sky);
+    map.insert("sun".to_string(), Symbol::Sun);
+    map.insert("turbidity".to_string(), Symbol::Turbidity);
+    map.insert("ground_albedo".to_string(), Symbol::GroundAlbedo);
+    map.insert("global_settings".to_string(), Symbol::GlobalSettings);
+    map.insert("photons".to_string(), Symbol::Photons);
+    map.insert("count".to_string(), Symbol::Count);
 
     map.insert("translate".to_string(), Symbol::Translate);
     map.insert("rotate".to_string(), Symbol::Rotate);
@@ -287,24 +483,37 @@ fn tokenize(line_in: &String, line_no: u32) -> Vec<Token> {
 
     let mut v = Vec::new();
 
-    for part in line.split_inclusive(&seps[..]) {
+    // quoted strings, like file names, are single tokens
+    for (i, segment) in line.split('"').enumerate() {
+        if i % 2 == 1 {
+            v.push(Token {text: format!("\"{segment}\""), line: line_no});
+        }
+        else {
+            tokenize_segment(segment, line_no, &seps, &mut v);
+        }
+    }
+
+    v
+}
+
+fn tokenize_segment(line: &str, line_no: u32, seps: &[char], v: &mut Vec<Token>) {
+
+    for part in line.split_inclusive(seps) {
         if part.ends_with(seps) {
             
             let mut chars = part.chars();
             let sep = chars.next_back();
             let left = part.strip_suffix(seps);
 
-            push_non_empty(&mut v, left.unwrap(), line_no);
-            push_non_empty(&mut v, &sep.unwrap().to_string(), line_no);
+            push_non_empty(v, left.unwrap(), line_no);
+            push_non_empty(v, &sep.unwrap().to_string(), line_no);
         }
         else {
             if part.len() > 0 {
-                push_non_empty(&mut v, part, line_no);
+                push_non_empty(v, part, line_no);
             }
         }
     }
-    
-    v
 }
 
 
@@ -339,6 +548,10 @@ fn to_symbol(map: &HashMap<String, Symbol>, token: &String) -> Symbol {
         return symbol.clone()
     }
 
+    if token.starts_with('"') {
+        return Symbol::Str;
+    }
+
     Symbol::Id
 }
 
@@ -412,6 +625,18 @@ fn parse_statement_list(input: &mut Input, scene: &mut SceneData) -> bool {
             DeclaredEntity::Camera(camera) => {
                 scene.camera = Some(camera);
             },
+            DeclaredEntity::Background(color) => {
+                scene.background = Some(color);
+            },
+            DeclaredEntity::Environment(environment) => {
+                scene.environment = Some(environment);
+            },
+            DeclaredEntity::Sky(sky) => {
+                scene.sky = Some(sky);
+            },
+            DeclaredEntity::Photons(photons) => {
+                scene.photons = Some(photons);
+            },
             DeclaredEntity::Directive(_ident) => {
                 // nothing to do here
             },
@@ -441,6 +666,18 @@ fn parse_statement(input: &mut Input) -> DeclaredEntity {
     let entity = parse_light(input);
     match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
 
+    let entity = parse_background(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_sky_sphere(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_sky(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_global_settings(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
     let entity = parse_sphere(input);
     match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
 
@@ -450,6 +687,21 @@ fn parse_statement(input: &mut Input) -> DeclaredEntity {
     let entity = parse_quadric(input);
     match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
 
+    let entity = parse_torus(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_cylinder(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_cone(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_disc(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
+    let entity = parse_plane(input);
+    match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
+
     let entity = parse_object(input);
     match entity { DeclaredEntity::Invalid => {}, _ => { return entity; },}
 
@@ -522,15 +774,25 @@ fn parse_light(input: &mut Input) -> DeclaredEntity {
             let mut light = LightData {
                 location: Vec3::default(), 
                 color: Color::default(), 
+                kind: LightKind::Sphere,
+                fade_distance: 0.0,
+                fade_power: 0.0,
             };
 
             if let Some(location) = parse_vector(input) {
                 expect(input, Symbol::Comma);
                 if let Some(color) = parse_color(input) {
-
-                    expect(input, Symbol::BlockClose);
                     light.location = location;
                     light.color = color;
+
+                    while input.symbol != Symbol::BlockClose {
+                        if !parse_light_modifier(input, &mut light) {
+                            //marked println!("Line {}, parse_light: expected light modifier or }}, found {}", input.current_line(), input.current_text());
+                            return DeclaredEntity::Invalid;
+                        }
+                    }
+
+                    nextsym(input);
                 }
                 else {
                     //marked println!("Line {}, parse_light: expected color vector, found {}", input.current_line(), input.current_text());
@@ -553,6 +815,277 @@ fn parse_light(input: &mut Input) -> DeclaredEntity {
 }
 
 
+/**
+ * Point lights, parallel lights and spotlights in the POV-Ray style:
+ * `light { <0, 10, 0>, color rgb <1, 1, 1> spotlight point_at <0, 0, 0> radius 20 falloff 30 }`
+ * Lights without a type keyword are glowing spheres.
+ */
+fn parse_light_modifier(input: &mut Input, light: &mut LightData) -> bool {
+
+    if accept(input, Symbol::Point) {
+        light.kind = LightKind::Point;
+    }
+    else if accept(input, Symbol::Parallel) {
+        light.kind = LightKind::Parallel { point_at: Vec3::default() };
+    }
+    else if accept(input, Symbol::Spotlight) {
+        light.kind = LightKind::Spot { point_at: Vec3::default(), radius: 30.0, falloff: 45.0, tightness: 0.0 };
+    }
+    else if accept(input, Symbol::FadeDistance) {
+        match parse_expression(input) {
+            Some(v) => light.fade_distance = v,
+            None => return false,
+        }
+    }
+    else if accept(input, Symbol::FadePower) {
+        match parse_expression(input) {
+            Some(v) => light.fade_power = v,
+            None => return false,
+        }
+    }
+    else {
+        return parse_light_direction(input, light);
+    }
+
+    true
+}
+
+
+// the direction of parallel lights and spotlights, and the cone of spotlights
+fn parse_light_direction(input: &mut Input, light: &mut LightData) -> bool {
+
+    if accept(input, Symbol::PointAt) {
+        let v = parse_vector(input);
+        match (&mut light.kind, v) {
+            (LightKind::Parallel { point_at } | LightKind::Spot { point_at, .. }, Some(v)) => *point_at = v,
+            _ => return false,
+        }
+    }
+    else if accept(input, Symbol::Radius) {
+        let v = parse_expression(input);
+        match (&mut light.kind, v) {
+            (LightKind::Spot { radius, .. }, Some(v)) => *radius = v,
+            _ => return false,
+        }
+    }
+    else if accept(input, Symbol::Falloff) {
+        let v = parse_expression(input);
+        match (&mut light.kind, v) {
+            (LightKind::Spot { falloff, .. }, Some(v)) => *falloff = v,
+            _ => return false,
+        }
+    }
+    else if accept(input, Symbol::Tightness) {
+        let v = parse_expression(input);
+        match (&mut light.kind, v) {
+            (LightKind::Spot { tightness, .. }, Some(v)) => *tightness = v,
+            _ => return false,
+        }
+    }
+    else {
+        return false;
+    }
+
+    true
+}
+
+
+/**
+ * A constant background color for rays which miss the scene:
+ *   background { color rgb <0.2, 0.3, 0.5> }
+ */
+fn parse_background(input: &mut Input) -> DeclaredEntity {
+    if expect_quiet(input, Symbol::Background) && expect(input, Symbol::BlockOpen) {
+        if let Some(color) = parse_color(input) {
+            if expect(input, Symbol::BlockClose) {
+                return DeclaredEntity::Background(color);
+            }
+        }
+        //marked println!("Line {}, parse_background: expected color, found {}", input.current_line(), input.current_text());
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+/**
+ * An HDR environment map in latitude-longitude layout, which lights the scene.
+ * The POV-Ray form has a spherical image map:
+ *   `sky_sphere { pigment { image_map { hdr "sky.hdr" map_type 1 } } rotate <0, 90, 0> }`
+ * Raysnail also takes the file directly, and a multiplier of its brightness:
+ *   `sky_sphere { hdr "sky.hdr" rotate <0, 90, 0> intensity 1.5 }`
+ * OpenEXR files are given with the exr keyword. Only the rotation around
+ * the y axis is used.
+ */
+fn parse_sky_sphere(input: &mut Input) -> DeclaredEntity {
+    if expect_quiet(input, Symbol::SkySphere) && expect(input, Symbol::BlockOpen) {
+        let mut filename = None;
+        let mut rotation = 0.0;
+        let mut intensity = 1.0;
+
+        while input.symbol != Symbol::BlockClose {
+            if accept(input, Symbol::Hdr) || accept(input, Symbol::Exr) {
+                filename = parse_string(input);
+            }
+            else if accept(input, Symbol::Pigment) {
+                filename = parse_sky_image_map(input);
+                if filename.is_none() {
+                    return DeclaredEntity::Invalid;
+                }
+            }
+            else if let Some(v) = parse_rotate(input) {
+                rotation = v.y;
+            }
+            else if accept(input, Symbol::Intensity) {
+                intensity = parse_expression(input).unwrap_or(1.0);
+            }
+            else {
+                //marked println!("Line {}, parse_sky_sphere: expected hdr, exr, rotate, intensity or }}, found {}", input.current_line(), input.current_text());
+                return DeclaredEntity::Invalid;
+            }
+        }
+
+        nextsym(input);
+
+        let filename = filename.unwrap_or_default();
+        match EnvironmentLight::load(&filename) {
+            Ok(environment) => {
+                return DeclaredEntity::Environment(Arc::new(environment.rotation(rotation).intensity(intensity)));
+            },
+            Err(message) => {
+                warn!("Line {}, could not load environment map '{}': {}", input.current_line(), filename, message);
+                return DeclaredEntity::Directive(filename);
+            },
+        }
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+// the image map of a sky sphere pigment, after the pigment keyword
+fn parse_sky_image_map(input: &mut Input) -> Option<String> {
+    if !(expect(input, Symbol::BlockOpen) && expect(input, Symbol::ImageMap) && expect(input, Symbol::BlockOpen)) {
+        return None;
+    }
+
+    if !(accept(input, Symbol::Hdr) || accept(input, Symbol::Exr)) {
+        //marked println!("Line {}, parse_sky_image_map: expected hdr or exr, found {}", input.current_line(), input.current_text());
+        return None;
+    }
+
+    let filename = parse_string(input);
+
+    if accept(input, Symbol::MapType) {
+        let map_type = parse_expression(input);
+        if map_type != Some(1.0) {
+            warn!("Line {}, sky sphere image maps are always spherical, map_type {:?} is ignored", input.current_line(), map_type);
+        }
+    }
+
+    if !(expect(input, Symbol::BlockClose) && expect(input, Symbol::BlockClose)) {
+        return None;
+    }
+
+    filename
+}
+
+
+/**
+ * A physical daylight sky with a matching sun light:
+ *   `sky { sun <1, 0.8, 0.3> turbidity 3 ground_albedo 0.3 intensity 1 }`
+ * The sun vector points from the scene towards the sun.
+ */
+fn parse_sky(input: &mut Input) -> DeclaredEntity {
+    if expect_quiet(input, Symbol::Sky) && expect(input, Symbol::BlockOpen) {
+        let mut sun = Vec3::new(0.5, 1.0, 0.3);
+        let mut turbidity = 3.0;
+        let mut ground_albedo = 0.3;
+        let mut intensity = 1.0;
+
+        while input.symbol != Symbol::BlockClose {
+            let ok = if accept(input, Symbol::Sun) {
+                parse_vector(input).map(|v| sun = v).is_some()
+            }
+            else if accept(input, Symbol::Turbidity) {
+                parse_expression(input).map(|v| turbidity = v).is_some()
+            }
+            else if accept(input, Symbol::GroundAlbedo) {
+                parse_expression(input).map(|v| ground_albedo = v).is_some()
+            }
+            else if accept(input, Symbol::Intensity) {
+                parse_expression(input).map(|v| intensity = v).is_some()
+            }
+            else {
+                false
+            };
+
+            if !ok {
+                //marked println!("Line {}, parse_sky: expected sun, turbidity, ground_albedo, intensity or }}, found {}", input.current_line(), input.current_text());
+                return DeclaredEntity::Invalid;
+            }
+        }
+
+        nextsym(input);
+
+        return DeclaredEntity::Sky(PhysicalSky::new(&sun)
+            .turbidity(turbidity)
+            .ground_albedo(ground_albedo)
+            .intensity(intensity));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+/**
+ * Global settings, so far only the photon map for caustics:
+ *   `global_settings { photons { count 200000 radius 0.05 } }`
+ */
+#[allow(clippy::cast_sign_loss)] // the count is clamped to zero
+fn parse_global_settings(input: &mut Input) -> DeclaredEntity {
+    if expect_quiet(input, Symbol::GlobalSettings) && expect(input, Symbol::BlockOpen) {
+        let mut entity = DeclaredEntity::Directive("global_settings".to_string());
+
+        while input.symbol != Symbol::BlockClose {
+            if accept(input, Symbol::Photons) && expect(input, Symbol::BlockOpen) {
+                let mut photons = PhotonData { count: 100_000, radius: None };
+
+                while input.symbol != Symbol::BlockClose {
+                    let ok = if accept(input, Symbol::Count) {
+                        parse_expression(input).map(|v| photons.count = v.max(0.0) as usize).is_some()
+                    }
+                    else if accept(input, Symbol::Radius) {
+                        parse_expression(input).map(|v| photons.radius = Some(v)).is_some()
+                    }
+                    else {
+                        false
+                    };
+
+                    if !ok {
+                        //marked println!("Line {}, parse_global_settings: expected count, radius or }}, found {}", input.current_line(), input.current_text());
+                        return DeclaredEntity::Invalid;
+                    }
+                }
+
+                nextsym(input);
+                entity = DeclaredEntity::Photons(photons);
+            }
+            else {
+                //marked println!("Line {}, parse_global_settings: expected photons or }}, found {}", input.current_line(), input.current_text());
+                return DeclaredEntity::Invalid;
+            }
+        }
+
+        nextsym(input);
+
+        return entity;
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
 fn parse_camera_item(input: &mut Input, camera: &mut CameraData) -> bool {
 
     if input.symbol == Symbol::Location {
@@ -587,16 +1120,17 @@ fn parse_sphere(input: &mut Input) -> DeclaredEntity {
             expect(input, Symbol::Comma);
             let r = parse_expression(input).unwrap();   
 
-            let material = parse_texture(input);
+            let (material, normal) = parse_texture(input);
+            let media = parse_media(input);
             let stack = parse_object_modifiers(input);
 
-            let sphere = Arc::new(Sphere::new(v, r, material));
+            let sphere = build_normal_facade(normal, Arc::new(Sphere::new(v, r, material)));
 
             //marked println!("parse_sphere: ok -> {:?}", sphere);
 
             expect(input, Symbol::BlockClose);
 
-            return DeclaredEntity::Hittable(build_transform_facade(stack, sphere));
+            return DeclaredEntity::Hittable(build_transform_facade(stack, build_media(media, sphere)));
         }
         else {
             //marked println!("Line {}, parse_sphere: expected {{, found {}", input.current_line(), input.current_text());
@@ -620,15 +1154,16 @@ fn parse_box(input: &mut Input) -> DeclaredEntity {
             expect(input, Symbol::Comma);
             let v2 = parse_vector(input).unwrap();
 
-            let material = parse_texture(input);
+            let (material, normal) = parse_texture(input);
+            let media = parse_media(input);
             let stack = parse_object_modifiers(input);
 
-            let gbox = Arc::new(GeometryBox::new(v1, v2, material));
+            let gbox = build_normal_facade(normal, Arc::new(GeometryBox::new(v1, v2, material)));
             //marked println!("parse_box: ok -> {:?}", gbox);
 
             expect(input, Symbol::BlockClose);
 
-            return DeclaredEntity::Hittable(build_transform_facade(stack, gbox));
+            return DeclaredEntity::Hittable(build_transform_facade(stack, build_media(media, gbox)));
         }
         else {
             //marked println!("Line {}, parse_box: expected {{, found {}", input.current_line(), input.current_text());
@@ -653,7 +1188,7 @@ fn parse_quadric(input: &mut Input) -> DeclaredEntity {
             expect(input, Symbol::Comma);
             let j = parse_expression(input).unwrap();
 
-            let material = parse_texture(input);
+            let (material, normal) = parse_texture(input);
             let stack = parse_object_modifiers(input);
 
             let quadric = 
@@ -664,7 +1199,7 @@ fn parse_quadric(input: &mut Input) -> DeclaredEntity {
 
             expect(input, Symbol::BlockClose);
 
-            return DeclaredEntity::Hittable(build_transform_facade(stack, Arc::new(quadric)));
+            return DeclaredEntity::Hittable(build_transform_facade(stack, build_normal_facade(normal, Arc::new(quadric))));
         }
         else {
             //marked println!("Line {}, parse_quadric: expected {{, found {}", input.current_line(), input.current_text());
@@ -675,6 +1210,153 @@ fn parse_quadric(input: &mut Input) -> DeclaredEntity {
 }
 
 
+fn parse_torus(input: &mut Input) -> DeclaredEntity {
+
+    //marked println!("Line {}, parse_torus: called, current symbol is {:?}", input.current_line(), input.current_text());
+
+    if expect_quiet(input, Symbol::Torus) && expect(input, Symbol::BlockOpen) {
+        let major = parse_expression(input).unwrap();
+        expect(input, Symbol::Comma);
+        let minor = parse_expression(input).unwrap();
+
+        let (material, normal) = parse_texture(input);
+        let media = parse_media(input);
+        let stack = parse_object_modifiers(input);
+
+        let torus = build_normal_facade(normal, Arc::new(Torus::new(major, minor, material)));
+        //marked println!("parse_torus: ok -> {:?}", torus);
+
+        expect(input, Symbol::BlockClose);
+
+        return DeclaredEntity::Hittable(build_transform_facade(stack, build_media(media, torus)));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+fn parse_cylinder(input: &mut Input) -> DeclaredEntity {
+
+    //marked println!("Line {}, parse_cylinder: called, current symbol is {:?}", input.current_line(), input.current_text());
+
+    if expect_quiet(input, Symbol::Cylinder) && expect(input, Symbol::BlockOpen) {
+        let base = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let cap = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let radius = parse_expression(input).unwrap();
+
+        // open may come before or after the texture and the transformations
+        let mut open = accept(input, Symbol::Open);
+        let (material, normal) = parse_texture(input);
+        let media = parse_media(input);
+        let stack = parse_object_modifiers(input);
+        open |= accept(input, Symbol::Open);
+
+        let mut cylinder = Cylinder::new(base, &cap, radius, material);
+        if open {
+            cylinder = cylinder.open();
+        }
+        //marked println!("parse_cylinder: ok -> {:?}", cylinder);
+
+        expect(input, Symbol::BlockClose);
+
+        let cylinder = build_normal_facade(normal, Arc::new(cylinder));
+        return DeclaredEntity::Hittable(build_transform_facade(stack, build_media(media, cylinder)));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+fn parse_cone(input: &mut Input) -> DeclaredEntity {
+
+    //marked println!("Line {}, parse_cone: called, current symbol is {:?}", input.current_line(), input.current_text());
+
+    if expect_quiet(input, Symbol::Cone) && expect(input, Symbol::BlockOpen) {
+        let base = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let base_radius = parse_expression(input).unwrap();
+        expect(input, Symbol::Comma);
+        let cap = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let cap_radius = parse_expression(input).unwrap();
+
+        let mut open = accept(input, Symbol::Open);
+        let (material, normal) = parse_texture(input);
+        let media = parse_media(input);
+        let stack = parse_object_modifiers(input);
+        open |= accept(input, Symbol::Open);
+
+        let mut cone = Cone::new(base, base_radius, &cap, cap_radius, material);
+        if open {
+            cone = cone.open();
+        }
+        //marked println!("parse_cone: ok -> {:?}", cone);
+
+        expect(input, Symbol::BlockClose);
+
+        let cone = build_normal_facade(normal, Arc::new(cone));
+        return DeclaredEntity::Hittable(build_transform_facade(stack, build_media(media, cone)));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+fn parse_disc(input: &mut Input) -> DeclaredEntity {
+
+    //marked println!("Line {}, parse_disc: called, current symbol is {:?}", input.current_line(), input.current_text());
+
+    if expect_quiet(input, Symbol::Disc) && expect(input, Symbol::BlockOpen) {
+        let center = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let normal_vector = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let radius = parse_expression(input).unwrap();
+
+        // optional hole radius
+        let hole = if accept(input, Symbol::Comma) { parse_expression(input).unwrap() } else { 0.0 };
+
+        let (material, normal) = parse_texture(input);
+        let stack = parse_object_modifiers(input);
+
+        let disc = Disc::new(center, &normal_vector, radius, material).hole(hole);
+        //marked println!("parse_disc: ok -> {:?}", disc);
+
+        expect(input, Symbol::BlockClose);
+
+        return DeclaredEntity::Hittable(build_transform_facade(stack, build_normal_facade(normal, Arc::new(disc))));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
+fn parse_plane(input: &mut Input) -> DeclaredEntity {
+
+    //marked println!("Line {}, parse_plane: called, current symbol is {:?}", input.current_line(), input.current_text());
+
+    if expect_quiet(input, Symbol::Plane) && expect(input, Symbol::BlockOpen) {
+        let normal_vector = parse_vector(input).unwrap();
+        expect(input, Symbol::Comma);
+        let distance = parse_expression(input).unwrap();
+
+        let (material, normal) = parse_texture(input);
+        let stack = parse_object_modifiers(input);
+
+        let plane = Plane::new(&normal_vector, distance, material);
+        //marked println!("parse_plane: ok -> {:?}", plane);
+
+        expect(input, Symbol::BlockClose);
+
+        return DeclaredEntity::Hittable(build_transform_facade(stack, build_normal_facade(normal, Arc::new(plane))));
+    }
+
+    DeclaredEntity::Invalid
+}
+
+
 fn parse_object(input: &mut Input) -> DeclaredEntity {
 
     //marked println!("Line {}, parse_object: called, current symbol is {:?}", input.current_line(), input.current_text());
@@ -738,10 +1420,10 @@ fn parse_difference(input: &mut Input) -> DeclaredEntity {
 
                     //marked println!("parse_difference: parsed second statement, now checking objects");
 
-                    let material = parse_texture(input);
+                    let (material, normal) = parse_texture(input);
                     let stack = parse_object_modifiers(input);
 
-                    let difference = Arc::new(Difference::new(plus, minus, material));
+                    let difference = build_normal_facade(normal, Arc::new(Difference::new(plus, minus, material)));
 
                     //marked println!("Line {}, parse_difference -> ok", input.current_line());
 
@@ -783,10 +1465,10 @@ fn parse_intersection(input: &mut Input) -> DeclaredEntity {
                 if let DeclaredEntity::Hittable(o2) = parse_statement(input) {
 
                     //marked println!("parse_intersection: parsed second statement, now checking objects");
-                    let material = parse_texture(input);
+                    let (material, normal) = parse_texture(input);
                     let stack = parse_object_modifiers(input);
 
-                    let intersection = Arc::new(Intersection::new(o1, o2, material));
+                    let intersection = build_normal_facade(normal, Arc::new(Intersection::new(o1, o2, material)));
 
                     //marked println!("Line {}, parse_intersection -> ok", input.current_line());
 
@@ -918,6 +1600,26 @@ fn parse_end(input: &mut Input) -> DeclaredEntity {
 }
 
 
+fn build_normal_facade(normal: Option<Arc<dyn NormalModifier>>, hittable: Arc<dyn Hittable>) -> Arc<dyn Hittable> {
+    match normal {
+        Some(normal) => Arc::new(NormalFacade::new(hittable, normal)),
+        None => hittable,
+    }
+}
+
+
+// media fill the object, which then has no surface anymore
+fn build_media(media: Option<MediaData>, hittable: Arc<dyn Hittable>) -> Arc<dyn Hittable> {
+    match media {
+        Some(media) => {
+            Arc::new(HeterogeneousMedium::new(hittable, media.color, media.max_density, media.density)
+                .phase(media.phase))
+        },
+        None => hittable,
+    }
+}
+
+
 fn build_transform_facade(stack: TransformStack, hittable: Arc<dyn Hittable>) ->  Arc<dyn Hittable> {
 
     if stack.len() > 0 {
@@ -964,7 +1666,7 @@ fn parse_object_modifiers(input: &mut Input) -> TransformStack {
     stack
 }
 
-fn parse_texture(input: &mut Input) -> Option<Arc<dyn Material>> {
+fn parse_texture(input: &mut Input) -> TextureData {
 
     if expect_quiet(input, Symbol::Texture) {
         if expect(input, Symbol::BlockOpen) {
@@ -977,17 +1679,23 @@ fn parse_texture(input: &mut Input) -> Option<Arc<dyn Material>> {
                     Arc::new(Color::new(1.0, 1.0, 1.0, 1.0))
                 };
 
-            let material = parse_finish(input, texture);
+            let normal = parse_normal(input);
+            let material = parse_finish(input, texture.clone());
 
             expect(input, Symbol::BlockClose);
 
             //marked println!("Line {}, parse_texture -> ok", input.current_line());
 
-            return material;
+            // an interior turns a transparent object into a dielectric
+            if let Some(interior) = parse_interior(input, &texture) {
+                return (Some(interior), normal);
+            }
+
+            return (material, normal);
         }
     }
 
-    None
+    (None, None)
 }
 
 fn parse_pigment(input: &mut Input) -> Option<Arc<dyn Texture>> {
@@ -1004,7 +1712,68 @@ fn parse_pigment(input: &mut Input) -> Option<Arc<dyn Texture>> {
                 expect(input, Symbol::BlockClose);
                 return Some(Arc::new(Checker::new(colors.0, colors.1, 2.0)));
             }
+            else if expect_quiet(input, Symbol::ImageMap) {
+                // image_map { png "file.png" }, mapped by the uv coordinates of the object
+                expect(input, Symbol::BlockOpen);
+                expect(input, Symbol::Png);
+                let filename = parse_string(input).unwrap_or_default();
+                expect(input, Symbol::BlockClose);
+                expect(input, Symbol::BlockClose);
+
+                match Image::new(&filename) {
+                    Ok(image) => return Some(Arc::new(image)),
+                    Err(message) => warn!("Line {}, could not load image map '{}': {}", input.current_line(), filename, message),
+                }
+            }
+        }
+    }
+
+    None
+}
+
+
+/**
+ * POV-Ray style normal perturbation, after the pigment:
+ *   `normal { bumps 0.4 scale 0.2 }`
+ *   `normal { bump_map { png "bumps.png" bump_size 2 } }`
+ * Bumps are a solid noise pattern, bump maps are mapped by the uv
+ * coordinates of the object.
+ */
+fn parse_normal(input: &mut Input) -> Option<Arc<dyn NormalModifier>> {
+
+    if expect_quiet(input, Symbol::Normal) && expect(input, Symbol::BlockOpen) {
+
+        let mut result: Option<Arc<dyn NormalModifier>> = None;
+
+        if expect_quiet(input, Symbol::Bumps) {
+            let amount = parse_float(input).unwrap();
+            let scale = if expect_quiet(input, Symbol::Scale) { parse_float(input).unwrap() } else { 1.0 };
+
+            // a fixed seed keeps renders of the scene reproducible,
+            // the bump height is a fraction of the pattern size
+            let mut rng = FastRng::new_seeded(0);
+            let noise = Perlin::new(256, true, &mut rng).scale(1.0 / scale);
+            result = Some(Arc::new(BumpMap::new(noise, amount * scale)));
+        }
+        else if expect_quiet(input, Symbol::BumpMapBlock) {
+            expect(input, Symbol::BlockOpen);
+            expect(input, Symbol::Png);
+
+            let filename = parse_string(input).unwrap_or_default();
+            let bump_size = if expect_quiet(input, Symbol::BumpSize) { parse_float(input).unwrap() } else { 1.0 };
+            expect(input, Symbol::BlockClose);
+
+            match Image::new(&filename) {
+                // slopes in uv space are steep, this keeps
+                // bump_size 1 at a moderate tilt
+                Ok(image) => result = Some(Arc::new(BumpMap::new(image, bump_size * 0.01))),
+                Err(message) => warn!("Line {}, could not load bump map '{}': {}", input.current_line(), filename, message),
+            }
         }
+
+        expect(input, Symbol::BlockClose);
+
+        return result;
     }
 
     None
@@ -1019,6 +1788,15 @@ fn parse_finish(input: &mut Input, texture: Arc<dyn Texture>) -> Option<Arc<dyn
             let mut phong = 0.0;
             let mut phong_size = 40.0;
             let mut reflection = 0.0;
+            let mut metallic = None;
+            let mut roughness = 0.0;
+            let mut specular = 0.0;
+            let mut sheen = 0.0;
+            let mut clearcoat = 0.0;
+            let mut transmission = 0.0;
+            let mut ior = 1.5;
+            let mut emission = None;
+            let mut nits = 1.0;
 
             loop {
                 if expect_quiet(input, Symbol::Reflection) {
@@ -1030,61 +1808,266 @@ fn parse_finish(input: &mut Input, texture: Arc<dyn Texture>) -> Option<Arc<dyn
                 else if expect_quiet(input, Symbol::PhongSize) {
                     phong_size = parse_float(input).unwrap();
                 }
+                else if expect_quiet(input, Symbol::Metallic) {
+                    metallic = parse_float(input);
+                }
+                else if expect_quiet(input, Symbol::Roughness) {
+                    roughness = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Specular) {
+                    specular = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Sheen) {
+                    sheen = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Clearcoat) {
+                    clearcoat = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Transmission) {
+                    transmission = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Ior) {
+                    ior = parse_float(input).unwrap();
+                }
+                else if expect_quiet(input, Symbol::Emission) {
+                    expect_quiet(input, Symbol::Rgb);
+                    emission = parse_vector(input);
+                }
+                else if expect_quiet(input, Symbol::Nits) {
+                    nits = parse_float(input).unwrap();
+                }
                 else {
                     break;
                 }
             }
             expect(input, Symbol::BlockClose);
 
-            let material: Arc<dyn Material> =
-                if reflection == 0.0 {
-                    let mut lambertian = Lambertian::new(texture);
-                    lambertian.set(settings(phong, phong_size));
+            // PovRay reflection mirrors the pigment color like a metal does
+            let mut principled = Principled::new(texture.clone())
+                .metallic(metallic.unwrap_or(reflection))
+                .roughness(roughness)
+                .specular(specular)
+                .sheen(sheen)
+                .clearcoat(clearcoat)
+                .transmission(transmission)
+                .refractive(ior);
+            principled.set(settings(phong, phong_size));
 
-                    Arc::new(lambertian)
-                }
-                else {
-                    let mut lambertian = Lambertian::new(texture.clone());
-                    lambertian.set(settings(phong, phong_size));
+            //marked println!("Line {}, parse_finish: using principled material, reflection={}", input.current_line(), reflection);
 
-                    let mut metal = Metal::new(texture);
-                    metal.set(settings(phong, phong_size));
+            let material: Arc<dyn Material> = Arc::new(principled);
 
-                    //marked println!("Line {}, parse_finish: using mixed material, reflection={}", input.current_line(), reflection);
+            // emissive surfaces glow in the pigment, tinted by the emission
+            // color, and reflect light like the rest of the finish says
+            if let Some(e) = emission {
+                let light = DiffuseLight::new(texture)
+                    .tint(Color::new64(e.x, e.y, e.z, 1.0))
+                    .nits(nits)
+                    .surface(material);
 
-                    Arc::new(MixedMaterial::new(Arc::new(metal), Arc::new(lambertian), reflection))
-                };
+                //marked println!("Line {}, parse_finish: using emissive material, nits={}", input.current_line(), nits);
+
+                return Some(Arc::new(light));
+            }
 
             return Some(material);
         }
     }
-    else if expect(input, Symbol::Surface) {
-        if expect(input, Symbol::BlockOpen) {
+    else if let Some(material) = parse_surface(input, texture.clone()) {
+        return Some(material);
+    }
 
-            let material: Arc<dyn Material> =
-                if expect_quiet(input, Symbol::Metallic) {
+    // Lambertian is default
+    Some(Arc::new(Lambertian::new(texture)))
+}
 
-                    if expect_quiet(input, Symbol::Diffuse) {
-                        //marked println!("Line {}, parse_surface: using diffuse metal", input.current_line());
-                        let v = parse_float(input).unwrap();
-                        Arc::new(DiffuseMetal::new(v, texture))
-                    }
-                    else {
-                        //marked println!("Line {}, parse_surface: using specular metal", input.current_line());
-                        Arc::new(Metal::new(texture))
-                    }
-                }
-                else {
-                    Arc::new(Lambertian::new(texture))
-                };
-            
+
+/**
+ * POV-Ray interior, following the texture of an object:
+ * `interior { ior 1.5 fade_distance 2 fade_power 1001 fade_color rgb <0, 0.5, 0.2> }`
+ * `interior { ior 1.5 absorption rgb <0.4, 0.2, 0.1> dispersion 1.02 }`
+ * `interior { ior 1.4 subsurface { mean_free_path 0.05 } }`
+ * Absorption coefficients are per unit of distance, dispersion is the
+ * ratio of the refractive index of violet light to that of red light.
+ * With subsurface scattering the pigment color is the albedo of the
+ * scattering events inside and the surface itself stays clear.
+ *
+ * Like in POV-Ray the rest of the interior only matters for objects which
+ * let light in, i.e. with a filter or transmit pigment. For opaque pigments
+ * it is parsed, but the finish of the texture is kept.
+ */
+fn parse_interior(input: &mut Input, texture: &Arc<dyn Texture>) -> Option<Arc<dyn Material>> {
+
+    if expect_quiet(input, Symbol::Interior) && expect(input, Symbol::BlockOpen) {
+        let interior = parse_interior_modifiers(input);
+        expect(input, Symbol::BlockClose);
+
+        // dielectrics have a uniform color
+        let color = texture.color(0.0, 0.0, &Point3::default());
+
+        if interior.mean_free_path.is_none() && color.a >= 1.0 {
+            //marked println!("Line {}, parse_interior: opaque pigment, keeping the finish", input.current_line());
+            return None;
+        }
+        let surface_color = if interior.mean_free_path.is_some() {
+            Color::new(1.0, 1.0, 1.0, 1.0)
+        } else {
+            Color::new(color.r, color.g, color.b, 1.0)
+        };
+        let mut dielectric = Dielectric::new(surface_color, interior.ior).reflect_curve(Glass {});
+
+        if (interior.dispersion - 1.0).abs() > 1e-9 {
+            dielectric = dielectric.dispersion(Dispersion::from_ratio(interior.ior, interior.dispersion));
+        }
+
+        if let Some(absorption) = interior.absorption {
+            dielectric = dielectric.absorption(absorption);
+        }
+        else if interior.fade_distance > 0.0 {
+            dielectric = dielectric.fade(interior.fade_distance, interior.fade_power, interior.fade_color);
+        }
+
+        //marked println!("Line {}, parse_interior: ok -> {:?}", input.current_line(), dielectric);
+
+        if let Some(mean_free_path) = interior.mean_free_path {
+            return Some(Arc::new(Subsurface::new(color, mean_free_path).boundary(dielectric)));
+        }
+
+        return Some(Arc::new(dielectric));
+    }
+
+    None
+}
+
+
+// the keywords inside the braces of an interior
+fn parse_interior_modifiers(input: &mut Input) -> InteriorData {
+    let mut interior = InteriorData {
+        ior: 1.0,
+        fade_distance: 0.0,
+        fade_power: 2.0,
+        fade_color: Color::new(0.0, 0.0, 0.0, 1.0),
+        absorption: None,
+        dispersion: 1.0,
+        mean_free_path: None,
+    };
+
+    loop {
+        if expect_quiet(input, Symbol::Ior) {
+            interior.ior = parse_float(input).unwrap();
+        }
+        else if expect_quiet(input, Symbol::FadeDistance) {
+            interior.fade_distance = parse_float(input).unwrap();
+        }
+        else if expect_quiet(input, Symbol::FadePower) {
+            interior.fade_power = parse_float(input).unwrap();
+        }
+        else if expect_quiet(input, Symbol::FadeColor) {
+            expect_quiet(input, Symbol::Rgb);
+            let v = parse_vector(input).unwrap();
+            interior.fade_color = Color::new64(v.x, v.y, v.z, 1.0);
+        }
+        else if expect_quiet(input, Symbol::Absorption) {
+            expect_quiet(input, Symbol::Rgb);
+            interior.absorption = parse_vector(input);
+        }
+        else if expect_quiet(input, Symbol::Dispersion) {
+            interior.dispersion = parse_float(input).unwrap();
+        }
+        else if expect_quiet(input, Symbol::Subsurface) {
+            expect(input, Symbol::BlockOpen);
+            expect(input, Symbol::MeanFreePath);
+            interior.mean_free_path = parse_float(input);
             expect(input, Symbol::BlockClose);
-            return Some(material);
+        }
+        else {
+            return interior;
         }
     }
+}
 
-    // Lambertian is default
-    Some(Arc::new(Lambertian::new(texture)))
+
+/**
+ * POV-Ray style media, which fill spheres and boxes:
+ * `media { scattering { 1, rgb <0.5, 0.5, 0.5> } density { bozo scale 0.8 } }`
+ * The scattering color is the scattering coefficient per unit of distance,
+ * the optional density pattern modulates it in space. Scattering type 5 is
+ * Henyey-Greenstein with an optional `eccentricity g`, all other types
+ * scatter isotropic.
+ */
+fn parse_media(input: &mut Input) -> Option<MediaData> {
+
+    if expect_quiet(input, Symbol::Media) && expect(input, Symbol::BlockOpen) {
+
+        let mut coefficients = Vec3::new(1.0, 1.0, 1.0);
+        let mut phase = PhaseFunction::Isotropic;
+        let mut density: Arc<dyn Texture> = Arc::new(1.0);
+
+        loop {
+            if expect_quiet(input, Symbol::Scattering) {
+                expect(input, Symbol::BlockOpen);
+                let kind = parse_float(input).unwrap();
+                expect(input, Symbol::Comma);
+                expect_quiet(input, Symbol::Rgb);
+                coefficients = parse_vector(input).unwrap();
+
+                if kind as i32 == 5 {
+                    let g = if expect_quiet(input, Symbol::Eccentricity) { parse_float(input).unwrap() } else { 0.0 };
+                    phase = PhaseFunction::HenyeyGreenstein(g);
+                }
+                expect(input, Symbol::BlockClose);
+            }
+            else if expect_quiet(input, Symbol::Density) {
+                density = parse_media_density(input);
+            }
+            else {
+                break;
+            }
+        }
+        expect(input, Symbol::BlockClose);
+
+        let max_density = coefficients.x.max(coefficients.y).max(coefficients.z);
+        if max_density <= 0.0 {
+            return None;
+        }
+
+        let color = Color::new64(coefficients.x / max_density, coefficients.y / max_density, coefficients.z / max_density, 1.0);
+
+        return Some(MediaData { color, phase, max_density, density });
+    }
+
+    None
+}
+
+
+// the block after the density keyword, a uniform density without a pattern
+fn parse_media_density(input: &mut Input) -> Arc<dyn Texture> {
+    expect(input, Symbol::BlockOpen);
+
+    let mut scale = 1.0;
+    let mut bozo = false;
+
+    loop {
+        if expect_quiet(input, Symbol::Bozo) {
+            bozo = true;
+        }
+        else if expect_quiet(input, Symbol::Scale) {
+            scale = parse_float(input).unwrap();
+        }
+        else {
+            break;
+        }
+    }
+    expect(input, Symbol::BlockClose);
+
+    if bozo {
+        // a fixed seed keeps renders of the scene reproducible
+        let mut rng = FastRng::new_seeded(0);
+        Arc::new(Perlin::new(256, true, &mut rng).scale(1.0 / scale))
+    }
+    else {
+        Arc::new(1.0)
+    }
 }
 
 
@@ -1112,11 +2095,43 @@ fn parse_surface(input: &mut Input, texture: Arc<dyn Texture>) -> Option<Arc<dyn
                         let v = parse_float(input).unwrap();
                         Arc::new(DiffuseMetal::new(v, texture))
                     }
+                    else if expect_quiet(input, Symbol::Roughness) {
+                        //marked println!("Line {}, parse_surface: using rough metal", input.current_line());
+                        let v = parse_float(input).unwrap();
+                        Arc::new(GgxConductor::new(texture, v))
+                    }
                     else {
                         //marked println!("Line {}, parse_surface: using specular metal", input.current_line());
                         Arc::new(Metal::new(texture))
                     }
                 }
+                else if expect_quiet(input, Symbol::Dielectric) {
+                    let mut ior = 1.5;
+                    let mut roughness = 0.0;
+
+                    loop {
+                        if expect_quiet(input, Symbol::Ior) {
+                            ior = parse_float(input).unwrap();
+                        }
+                        else if expect_quiet(input, Symbol::Roughness) {
+                            roughness = parse_float(input).unwrap();
+                        }
+                        else {
+                            break;
+                        }
+                    }
+
+                    // dielectrics have a uniform color
+                    let color = texture.color(0.0, 0.0, &Point3::default());
+
+                    if roughness > 0.0 {
+                        //marked println!("Line {}, parse_surface: using rough dielectric", input.current_line());
+                        Arc::new(GgxDielectric::new(color, ior, roughness))
+                    }
+                    else {
+                        Arc::new(Dielectric::new(color, ior).reflect_curve(Glass {}))
+                    }
+                }
                 else {
                     Arc::new(Lambertian::new(texture))
                 };
@@ -1164,6 +2179,18 @@ fn parse_identifier(input: &mut Input) -> Option<String> {
 }
 
 
+fn parse_string(input: &mut Input) -> Option<String> {
+    if input.symbol == Symbol::Str {
+        let text = input.current_text().trim_matches('"').to_string();
+        nextsym(input);
+
+        return Some(text);
+    }
+
+    None
+}
+
+
 fn parse_translate(input: &mut Input) -> Option<Vec3> {
 
     //marked println!("parse_translate: called");
@@ -1218,12 +2245,28 @@ fn parse_scale(input: &mut Input) -> Option<Vec3> {
 }
 
 
+/**
+ * POV-Ray color, optionally with filter and transmit:
+ * `color rgb <1, 0.8, 0.8> filter 0.9`
+ * Both make the color transparent, the alpha channel is 1 - filter - transmit.
+ */
 fn parse_color(input: &mut Input) -> Option<Color> {
     if expect_quiet(input, Symbol::Color) {
         expect_quiet(input, Symbol::Rgb);   // should this be made mandatory?
 
         if let Some(v) = parse_vector(input) {
-            return Some(Color::new64(v.x, v.y, v.z, 1.0))
+            let mut alpha = 1.0;
+
+            loop {
+                if expect_quiet(input, Symbol::Filter) || expect_quiet(input, Symbol::Transmit) {
+                    alpha -= parse_float(input).unwrap();
+                }
+                else {
+                    break;
+                }
+            }
+
+            return Some(Color::new64(v.x, v.y, v.z, alpha.clamp(0.0, 1.0)))
         } else {
             //marked println!("Line {}, parse_color: expected color vector, but found '{}'", input.current_line(), input.current_text());
         }
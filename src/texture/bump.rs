@@ -0,0 +1,127 @@
+use crate::{
+    hittable::HitRecord,
+    prelude::*,
+    texture::Texture,
+};
+
+use std::fmt::Formatter;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+
+// step width of the finite differences, in world units and in uv units
+const POINT_DELTA: f64 = 0.0005;
+const UV_DELTA: f64 = 0.0005;
+
+
+/**
+ * Perturbs the shading normal at a hit, before the material scatters the
+ * ray. Objects get a normal modifier with `NormalFacade`.
+ */
+pub trait NormalModifier: Send + Sync + Debug {
+    fn perturb(&self, hit: &HitRecord) -> Vec3;
+}
+
+
+/**
+ * Bumps from a height field. The red channel of the texture is the height,
+ * its slope is found by finite differences, in space for solid textures like
+ * `Perlin` and in uv space for image textures.
+ */
+pub struct BumpMap {
+    height: Arc<dyn Texture>,
+    strength: f64,
+}
+
+impl Debug for BumpMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "BumpMap {{ strength: {} }}", self.strength
+        ))
+    }
+}
+
+impl BumpMap {
+    #[must_use]
+    pub fn new<T: Texture + 'static>(height: T, strength: f64) -> Self {
+        Self {
+            height: Arc::new(height),
+            strength,
+        }
+    }
+
+    fn height(&self, u: f64, v: f64, point: &Point3) -> f64 {
+        f64::from(self.height.color(u, v, point).r)
+    }
+}
+
+impl NormalModifier for BumpMap {
+    #[allow(clippy::similar_names)] // the slopes along u and v
+    fn perturb(&self, hit: &HitRecord) -> Vec3 {
+        let (u, v, p) = (hit.u, hit.v, &hit.point);
+
+        let mut gradient = Vec3::default();
+        for axis in 0 .. 3 {
+            let mut d = Vec3::default();
+            d[axis] = POINT_DELTA;
+            gradient[axis] = (self.height(u, v, &(p + &d)) - self.height(u, v, &(p - &d))) / (2.0 * POINT_DELTA);
+        }
+
+        // only the slope along the surface tilts the normal
+        let mut slope = &gradient - gradient.dot(&hit.normal) * &hit.normal;
+
+        let dh_du = (self.height(u + UV_DELTA, v, p) - self.height(u - UV_DELTA, v, p)) / (2.0 * UV_DELTA);
+        let dh_dv = (self.height(u, v + UV_DELTA, p) - self.height(u, v - UV_DELTA, p)) / (2.0 * UV_DELTA);
+        slope += dh_du * &hit.tangent + dh_dv * &hit.bitangent;
+
+        (&hit.normal - self.strength * slope).unit()
+    }
+}
+
+
+/**
+ * Tangent space normal map, usually an `Image`. The red and green channels
+ * are the tilt along the tangent and the bitangent, blue is the part along
+ * the normal, each mapped from 0 .. 1 to -1 .. 1.
+ */
+pub struct NormalMap {
+    map: Arc<dyn Texture>,
+    strength: f64,
+}
+
+impl Debug for NormalMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "NormalMap {{ strength: {} }}", self.strength
+        ))
+    }
+}
+
+impl NormalMap {
+    #[must_use]
+    pub fn new<T: Texture + 'static>(map: T) -> Self {
+        Self {
+            map: Arc::new(map),
+            strength: 1.0,
+        }
+    }
+
+    /// Scales the tilt of the normals, 0 gives a flat surface
+    #[must_use]
+    pub const fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl NormalModifier for NormalMap {
+    fn perturb(&self, hit: &HitRecord) -> Vec3 {
+        let c = self.map.color(hit.u, hit.v, &hit.point);
+
+        let x = f64::from(c.r).mul_add(2.0, -1.0) * self.strength;
+        let y = f64::from(c.g).mul_add(2.0, -1.0) * self.strength;
+        let z = f64::from(c.b).mul_add(2.0, -1.0).max(0.0);
+
+        hit.onb().local(&Vec3::new(x, y, z)).unit()
+    }
+}
@@ -0,0 +1,671 @@
+use {
+    crate::{prelude::*, texture::Texture},
+    std::{
+        convert::TryFrom,
+        fmt::{Debug, Formatter},
+        fs::read,
+        path::Path,
+    },
+};
+
+
+/**
+ * An image with linear, unclamped colors, as used for environment maps.
+ * Radiance RGBE files (.hdr, .pic) and scanline OpenEXR files (.exr) with
+ * half or float channels, uncompressed or RLE/ZIP compressed, can be loaded.
+ */
+pub struct HdrImage {
+    width: usize,
+    height: usize,
+    // rows from top to bottom
+    pixels: Vec<Vec3>,
+}
+
+impl Debug for HdrImage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "HdrImage {{ {}x{} }} ",
+            self.width,
+            self.height
+        ))
+    }
+}
+
+impl HdrImage {
+    /// # Errors
+    ///
+    /// When the pixels don't match the size
+    pub fn new(width: usize, height: usize, pixels: Vec<Vec3>) -> Result<Self, String> {
+        if width == 0 || height == 0 || pixels.len() != width * height {
+            return Err(format!("{} pixels don't make a {}x{} image", pixels.len(), width, height));
+        }
+
+        Ok(Self { width, height, pixels })
+    }
+
+    /// # Errors
+    ///
+    /// When the file can't be read or is no supported HDR image
+    pub fn load<P: AsRef<Path>>(p: P) -> Result<Self, String> {
+        let data = read(p.as_ref()).map_err(|e| e.to_string())?;
+
+        if data.starts_with(&[0x76, 0x2f, 0x31, 0x01]) {
+            read_exr(&data)
+        }
+        else if data.starts_with(b"#?") {
+            read_radiance(&data)
+        }
+        else {
+            Err(format!("{} is no Radiance or OpenEXR file", p.as_ref().display()))
+        }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixel in column x and row y, row 0 is the top of the image
+    #[must_use]
+    pub fn pixel(&self, x: usize, y: usize) -> &Vec3 {
+        &self.pixels[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+}
+
+impl Texture for HdrImage {
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation, clippy::cast_precision_loss)] // u v and width all non-negative
+    fn color(&self, u: f64, v: f64, _point: &Point3) -> Color {
+        let x = (u.clamp(0.0, 1.0) * self.width as f64) as usize;
+        let y = ((1.0 - v.clamp(0.0, 1.0)) * self.height as f64) as usize;
+        let p = self.pixel(x, y);
+        Color::new64(p.x, p.y, p.z, 1.0)
+    }
+}
+
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if n > self.data.len() - self.pos {
+            return Err("Unexpected end of image file".to_string());
+        }
+        let bytes = &self.data[self.pos .. self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        let b = self.bytes(4)?;
+        Ok(i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    // text up to the terminator, which is skipped
+    fn text(&mut self, terminator: u8) -> Result<String, String> {
+        let start = self.pos;
+        while self.byte()? != terminator {}
+        Ok(String::from_utf8_lossy(&self.data[start .. self.pos - 1]).to_string())
+    }
+}
+
+
+// larger images are taken for broken files, 16k x 8k is the largest
+// environment map in common use
+const MAX_SIDE: usize = 1 << 16;
+const MAX_PIXELS: usize = 1 << 27;
+
+
+fn check_size(width: usize, height: usize) -> Result<(), String> {
+    if width == 0 || height == 0 || width > MAX_SIDE || height > MAX_SIDE || width * height > MAX_PIXELS {
+        return Err(format!("Bad image size {width}x{height}"));
+    }
+
+    Ok(())
+}
+
+
+fn rgbe(r: u8, g: u8, b: u8, e: u8) -> Vec3 {
+    if e == 0 {
+        return Vec3::default();
+    }
+
+    // the mantissas are fractions of 256
+    let f = 2.0_f64.powi(i32::from(e) - 136);
+    Vec3::new(f64::from(r) * f, f64::from(g) * f, f64::from(b) * f)
+}
+
+
+/**
+ * Radiance RGBE format with its run length encoded scanlines. Only the
+ * standard orientation "-Y height +X width" is supported.
+ */
+fn read_radiance(data: &[u8]) -> Result<HdrImage, String> {
+    let mut reader = Reader { data, pos: 0 };
+
+    loop {
+        let line = reader.text(b'\n')?;
+        if line.starts_with("FORMAT=") && line.trim() != "FORMAT=32-bit_rle_rgbe" {
+            return Err(format!("Unsupported Radiance pixel format '{}'", line.trim()));
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let resolution = reader.text(b'\n')?;
+    let parts: Vec<&str> = resolution.split_whitespace().collect();
+    if parts.len() != 4 || parts[0] != "-Y" || parts[2] != "+X" {
+        return Err(format!("Unsupported Radiance image orientation '{resolution}'"));
+    }
+    let height: usize = parts[1].parse().map_err(|_| "Bad Radiance image height".to_string())?;
+    let width: usize = parts[3].parse().map_err(|_| "Bad Radiance image width".to_string())?;
+    check_size(width, height)?;
+
+    let mut pixels = Vec::with_capacity(width * height);
+    let mut line = vec![0_u8; width * 4];
+
+    for _ in 0 .. height {
+        let head = reader.bytes(4)?;
+        let rle = (8 ..= 0x7fff).contains(&width) && head[0] == 2 && head[1] == 2 && head[2] & 0x80 == 0;
+
+        if rle {
+            if (usize::from(head[2]) << 8 | usize::from(head[3])) != width {
+                return Err("Bad Radiance scanline length".to_string());
+            }
+
+            read_radiance_rle(&mut reader, &mut line)?;
+        }
+        else {
+            // flat pixels
+            line[.. 4].copy_from_slice(head);
+            line[4 ..].copy_from_slice(reader.bytes((width - 1) * 4)?);
+        }
+
+        pixels.extend(line.chunks(4).map(|p| rgbe(p[0], p[1], p[2], p[3])));
+    }
+
+    HdrImage::new(width, height, pixels)
+}
+
+
+// a scanline with the four components stored one after the other
+fn read_radiance_rle(reader: &mut Reader<'_>, line: &mut [u8]) -> Result<(), String> {
+    let width = line.len() / 4;
+
+    for channel in 0 .. 4 {
+        let mut x = 0;
+        while x < width {
+            let count = usize::from(reader.byte()?);
+            if count > 128 {
+                let value = reader.byte()?;
+                for _ in 0 .. (count - 128).min(width - x) {
+                    line[x * 4 + channel] = value;
+                    x += 1;
+                }
+            }
+            else {
+                if count == 0 || x + count > width {
+                    return Err("Bad Radiance run length".to_string());
+                }
+                for &value in reader.bytes(count)? {
+                    line[x * 4 + channel] = value;
+                    x += 1;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+
+fn half_to_f32(h: u16) -> f32 {
+    let sign = u32::from(h >> 15) << 31;
+    let exponent = u32::from((h >> 10) & 0x1f);
+    let mantissa = u32::from(h & 0x3ff);
+
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // subnormal half, normalize it
+            let shift = mantissa.leading_zeros() - 21;
+            let mantissa = (mantissa << shift) & 0x3ff;
+            sign | ((113 - shift) << 23) | (mantissa << 13)
+        },
+        (0x1f, _) => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+
+struct ExrChannel {
+    name: String,
+    // 0 = uint, 1 = half, 2 = float
+    pixel_type: i32,
+}
+
+impl ExrChannel {
+    const fn size(&self) -> usize {
+        if self.pixel_type == 1 { 2 } else { 4 }
+    }
+
+    fn value(&self, b: &[u8]) -> f64 {
+        match self.pixel_type {
+            0 => f64::from(u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            1 => f64::from(half_to_f32(u16::from_le_bytes([b[0], b[1]]))),
+            _ => f64::from(f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        }
+    }
+}
+
+
+/**
+ * Undoes the byte reordering and delta encoding which OpenEXR applies
+ * before RLE and ZIP compression.
+ */
+fn exr_reconstruct(mut t: Vec<u8>) -> Vec<u8> {
+    for i in 1 .. t.len() {
+        t[i] = t[i - 1].wrapping_add(t[i]).wrapping_sub(128);
+    }
+
+    let half = t.len().div_ceil(2);
+    let mut out = Vec::with_capacity(t.len());
+    for i in 0 .. half {
+        out.push(t[i]);
+        if half + i < t.len() {
+            out.push(t[half + i]);
+        }
+    }
+
+    out
+}
+
+
+fn exr_rle(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut reader = Reader { data, pos: 0 };
+
+    while reader.pos < data.len() {
+        let count = reader.byte()?.cast_signed();
+        if count < 0 {
+            out.extend_from_slice(reader.bytes(usize::from(count.unsigned_abs()))?);
+        }
+        else {
+            let value = reader.byte()?;
+            out.extend(std::iter::repeat_n(value, usize::from(count.unsigned_abs()) + 1));
+        }
+    }
+
+    Ok(out)
+}
+
+
+struct ExrHeader {
+    channels: Vec<ExrChannel>,
+    compression: u8,
+    window: Option<(i32, i32, i32, i32)>,
+}
+
+// the attributes which are needed to read the pixels, the others are skipped
+fn read_exr_header(reader: &mut Reader<'_>) -> Result<ExrHeader, String> {
+    let mut channels = Vec::new();
+    let mut compression = 0;
+    let mut window = None;
+
+    loop {
+        let name = reader.text(0)?;
+        if name.is_empty() {
+            break;
+        }
+        let _kind = reader.text(0)?;
+        let size = usize::try_from(reader.i32()?).map_err(|_| "Bad OpenEXR attribute size".to_string())?;
+        let value = reader.bytes(size)?;
+
+        match name.as_str() {
+            "channels" => {
+                let mut attribute = Reader { data: value, pos: 0 };
+                loop {
+                    let name = attribute.text(0)?;
+                    if name.is_empty() {
+                        break;
+                    }
+                    let pixel_type = attribute.i32()?;
+                    // linear flag, reserved bytes and sampling
+                    attribute.bytes(12)?;
+                    channels.push(ExrChannel { name, pixel_type });
+                }
+            },
+            "compression" => compression = *value.first().ok_or("Bad OpenEXR compression")?,
+            "dataWindow" => {
+                let mut attribute = Reader { data: value, pos: 0 };
+                window = Some((attribute.i32()?, attribute.i32()?, attribute.i32()?, attribute.i32()?));
+            },
+            _ => {},
+        }
+    }
+
+    Ok(ExrHeader { channels, compression, window })
+}
+
+
+// the lines of a chunk, with their expected size in bytes
+fn exr_unpack(compression: u8, packed: &[u8], expected: usize) -> Result<Vec<u8>, String> {
+    let block = if packed.len() == expected {
+        // chunks which don't get smaller by compression are stored raw
+        packed.to_vec()
+    }
+    else {
+        match compression {
+            1 => exr_reconstruct(exr_rle(packed)?),
+            2 | 3 => exr_reconstruct(miniz_oxide::inflate::decompress_to_vec_zlib(packed)
+                .map_err(|e| format!("Bad OpenEXR zip data: {e:?}"))?),
+            _ => packed.to_vec(),
+        }
+    };
+
+    if block.len() < expected {
+        return Err("OpenEXR chunk is too short".to_string());
+    }
+
+    Ok(block)
+}
+
+
+/**
+ * Single part scanline OpenEXR files. The image is built from the R, G and
+ * B channels, luminance only images from the Y channel.
+ */
+fn read_exr(data: &[u8]) -> Result<HdrImage, String> {
+    let mut reader = Reader { data, pos: 4 };
+
+    let version = reader.i32()?;
+    if version & 0x1e00 != 0 {
+        return Err("Only single part scanline OpenEXR files are supported".to_string());
+    }
+
+    let ExrHeader { channels, compression, window } = read_exr_header(&mut reader)?;
+
+    let (x_min, y_min, x_max, y_max) = window.ok_or("OpenEXR file without data window")?;
+    let extent = |min: i32, max: i32| usize::try_from(i64::from(max) - i64::from(min) + 1).unwrap_or(0);
+    let width = extent(x_min, x_max);
+    let height = extent(y_min, y_max);
+    check_size(width, height)?;
+
+    let lines_per_chunk = match compression {
+        0 ..= 2 => 1,
+        3 => 16,
+        _ => return Err(format!("Unsupported OpenEXR compression {compression}")),
+    };
+
+    let find = |names: &[&str]| channels.iter().position(|c| names.contains(&c.name.as_str()));
+    let rgb = [find(&["R", "Y"]), find(&["G", "Y"]), find(&["B", "Y"])];
+
+    let line_size: usize = channels.iter().map(|c| c.size() * width).sum();
+    let chunks = height.div_ceil(lines_per_chunk);
+    let mut pixels = vec![Vec3::default(); width * height];
+
+    // the offset table is not needed, the chunks follow each other
+    reader.bytes(chunks * 8)?;
+
+    for _ in 0 .. chunks {
+        let y = usize::try_from(i64::from(reader.i32()?) - i64::from(y_min))
+            .ok()
+            .filter(|y| *y < height)
+            .ok_or("OpenEXR chunk outside of the data window")?;
+        let size = usize::try_from(reader.i32()?).map_err(|_| "Bad OpenEXR chunk size".to_string())?;
+        let packed = reader.bytes(size)?;
+        let lines = lines_per_chunk.min(height - y);
+        let expected = line_size * lines;
+
+        let block = exr_unpack(compression, packed, expected)?;
+
+        for line in 0 .. lines {
+            let row = &block[line * line_size .. (line + 1) * line_size];
+
+            // each line stores all values of one channel after the other
+            let mut offset = 0;
+            for (index, channel) in channels.iter().enumerate() {
+                for x in 0 .. width {
+                    let value = channel.value(&row[offset + x * channel.size() ..]);
+                    let pixel = &mut pixels[(y + line) * width + x];
+                    if rgb[0] == Some(index) { pixel.x = value; }
+                    if rgb[1] == Some(index) { pixel.y = value; }
+                    if rgb[2] == Some(index) { pixel.z = value; }
+                }
+                offset += channel.size() * width;
+            }
+        }
+    }
+
+    HdrImage::new(width, height, pixels)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // half of a float which has an exact half representation
+    fn half(v: f32) -> u16 {
+        if v == 0.0 {
+            return 0;
+        }
+        let bits = v.to_bits();
+        ((bits >> 16) & 0x8000) as u16 | ((((bits >> 23) & 0xff) - 112) << 10) as u16 | ((bits & 0x7f_ffff) >> 13) as u16
+    }
+
+    fn pattern(x: usize, y: usize) -> [f32; 3] {
+        [(x % 4) as f32 * 0.25, y as f32 * 0.5, 1.0]
+    }
+
+    // one scanline, the channels are stored in the order B, G, R
+    fn exr_line(width: usize, y: usize, float: bool) -> Vec<u8> {
+        let mut line = Vec::new();
+        for channel in [2, 1, 0] {
+            for x in 0 .. width {
+                let v = pattern(x, y)[channel];
+                if float {
+                    line.extend_from_slice(&v.to_le_bytes());
+                }
+                else {
+                    line.extend_from_slice(&half(v).to_le_bytes());
+                }
+            }
+        }
+        line
+    }
+
+    // the inverse of exr_reconstruct()
+    fn exr_predict(data: &[u8]) -> Vec<u8> {
+        let mut t: Vec<u8> = data.iter().step_by(2).chain(data.iter().skip(1).step_by(2)).copied().collect();
+        for i in (1 .. t.len()).rev() {
+            t[i] = t[i].wrapping_sub(t[i - 1]).wrapping_add(128);
+        }
+        t
+    }
+
+    fn exr_pack_rle(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut i = 0;
+
+        while i < data.len() {
+            let run = data[i ..].iter().take(128).take_while(|b| **b == data[i]).count();
+            if run >= 3 {
+                out.push((run - 1) as u8);
+                out.push(data[i]);
+                i += run;
+            }
+            else {
+                let start = i;
+                while i < data.len() && i - start < 128
+                    && !(i + 2 < data.len() && data[i] == data[i + 1] && data[i] == data[i + 2]) {
+                    i += 1;
+                }
+                out.push((-((i - start) as i32)) as u8);
+                out.extend_from_slice(&data[start .. i]);
+            }
+        }
+
+        out
+    }
+
+    fn exr_file(float: bool, compression: u8, window: [i32; 4], chunks: &[(i32, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+
+        let mut attribute = |name: &str, kind: &str, value: &[u8]| {
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+            data.extend_from_slice(kind.as_bytes());
+            data.push(0);
+            data.extend_from_slice(&(value.len() as i32).to_le_bytes());
+            data.extend_from_slice(value);
+        };
+
+        let mut channels = Vec::new();
+        for name in ["B", "G", "R"] {
+            channels.extend_from_slice(name.as_bytes());
+            channels.push(0);
+            channels.extend_from_slice(&(if float { 2_i32 } else { 1 }).to_le_bytes());
+            channels.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+        }
+        channels.push(0);
+
+        let window: Vec<u8> = window.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        attribute("channels", "chlist", &channels);
+        attribute("compression", "compression", &[compression]);
+        attribute("dataWindow", "box2i", &window);
+        data.push(0);
+
+        data.extend(std::iter::repeat_n(0, chunks.len() * 8));
+        for (y, chunk) in chunks {
+            data.extend_from_slice(&y.to_le_bytes());
+            data.extend_from_slice(&(chunk.len() as i32).to_le_bytes());
+            data.extend_from_slice(chunk);
+        }
+
+        data
+    }
+
+    fn assert_pattern(image: &HdrImage, width: usize, height: usize) {
+        assert_eq!((image.width(), image.height()), (width, height));
+        for y in 0 .. height {
+            for x in 0 .. width {
+                let [r, g, b] = pattern(x, y);
+                let p = image.pixel(x, y);
+                assert_eq!((p.x, p.y, p.z), (f64::from(r), f64::from(g), f64::from(b)), "pixel {} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_half() {
+        assert_eq!(half_to_f32(0x3c00), 1.0);
+        assert_eq!(half_to_f32(0xc000), -2.0);
+        assert_eq!(half_to_f32(0x3555), 0.333_251_95);
+        // the smallest subnormal
+        assert_eq!(half_to_f32(0x0001), 2.0_f32.powi(-24));
+        assert_eq!(half_to_f32(0x7c00), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_radiance_rle_and_flat() {
+        let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 8\n".to_vec();
+
+        // run length encoded, red as one run, green as literals,
+        // blue as two runs and the exponent 2^-7 as one run
+        data.extend_from_slice(&[2, 2, 0, 8]);
+        data.extend_from_slice(&[128 + 8, 128]);
+        data.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+        data.extend_from_slice(&[128 + 4, 64, 128 + 4, 0]);
+        data.extend_from_slice(&[128 + 8, 129]);
+
+        // flat pixels
+        for _ in 0 .. 8 {
+            data.extend_from_slice(&[64, 0, 0, 129]);
+        }
+
+        let image = read_radiance(&data).unwrap();
+        assert_eq!((image.width(), image.height()), (8, 2));
+        assert_eq!(image.pixel(0, 0), &Vec3::new(1.0, 0.0, 0.5));
+        assert_eq!(image.pixel(7, 0), &Vec3::new(1.0, 0.875, 0.0));
+        assert_eq!(image.pixel(3, 1), &Vec3::new(0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_radiance_bad_size() {
+        assert!(read_radiance(b"#?RADIANCE\n\n-Y 0 +X 8\n").is_err());
+        assert!(read_radiance(b"#?RADIANCE\n\n-Y 99999999 +X 99999999\n").is_err());
+    }
+
+    #[test]
+    fn test_exr_uncompressed_half() {
+        let (width, height) = (16, 4);
+        let chunks: Vec<_> = (0 .. height).map(|y| (y as i32, exr_line(width, y, false))).collect();
+
+        let image = read_exr(&exr_file(false, 0, [0, 0, 15, 3], &chunks)).unwrap();
+        assert_pattern(&image, width, height);
+    }
+
+    #[test]
+    fn test_exr_rle_half() {
+        let (width, height) = (16, 4);
+        let chunks: Vec<_> = (0 .. height).map(|y| {
+            let line = exr_line(width, y, false);
+            let packed = exr_pack_rle(&exr_predict(&line));
+            assert!(packed.len() < line.len());
+            (y as i32, packed)
+        }).collect();
+
+        let image = read_exr(&exr_file(false, 1, [0, 0, 15, 3], &chunks)).unwrap();
+        assert_pattern(&image, width, height);
+    }
+
+    #[test]
+    fn test_exr_zip_float() {
+        let (width, height) = (16, 20);
+
+        // 16 lines per chunk, the data window starts at y = 10
+        let chunks: Vec<_> = [0, 16].iter().map(|start| {
+            let lines: Vec<u8> = (*start .. height.min(start + 16)).flat_map(|y| exr_line(width, y, true)).collect();
+            let packed = miniz_oxide::deflate::compress_to_vec_zlib(&exr_predict(&lines), 6);
+            assert!(packed.len() < lines.len());
+            (*start as i32 + 10, packed)
+        }).collect();
+
+        let image = read_exr(&exr_file(true, 3, [0, 10, 15, 29], &chunks)).unwrap();
+        assert_pattern(&image, width, height);
+    }
+
+    #[test]
+    fn test_exr_bad_data_window() {
+        let chunk = vec![(0, exr_line(16, 0, false))];
+
+        assert!(read_exr(&exr_file(false, 0, [0, 0, -1, 0], &chunk)).is_err());
+        assert!(read_exr(&exr_file(false, 0, [5, 0, 0, 0], &chunk)).is_err());
+        assert!(read_exr(&exr_file(false, 0, [i32::MIN, 0, i32::MAX, 0], &chunk)).is_err());
+        assert!(read_exr(&exr_file(false, 0, [0, 0, 1 << 20, 1 << 20], &chunk)).is_err());
+
+        // a chunk outside of the window
+        assert!(read_exr(&exr_file(false, 0, [0, 0, 15, 0], &[(7, exr_line(16, 0, false))])).is_err());
+
+        // a truncated file
+        let file = exr_file(false, 0, [0, 0, 15, 0], &chunk);
+        assert!(read_exr(&file[.. file.len() - 10]).is_err());
+        assert!(read_exr(&file[.. 40]).is_err());
+    }
+}
@@ -1,12 +1,16 @@
 use {crate::prelude::*, std::sync::Arc};
 
+pub(crate) mod bump;
 pub(crate) mod checker;
+pub(crate) mod hdr;
 pub(crate) mod image;
 pub(crate) mod noise;
 
 pub use {
     self::image::Image,
+    bump::{NormalModifier, BumpMap, NormalMap},
     checker::Checker,
+    hdr::HdrImage,
     noise::{Perlin, SmoothType},
 };
 
@@ -14,8 +18,15 @@ pub trait Texture: Send + Sync {
     fn color(&self, u: f64, v: f64, point: &Point3) -> Color;
 }
 
-impl<T: Texture> Texture for Arc<T> {
+impl<T: Texture + ?Sized> Texture for Arc<T> {
     fn color(&self, u: f64, v: f64, point: &Point3) -> Color {
         self.as_ref().color(u, v, point)
     }
 }
+
+// a uniform grey value, useful for material parameters
+impl Texture for f64 {
+    fn color(&self, _u: f64, _v: f64, _point: &Point3) -> Color {
+        Color::new64(*self, *self, *self, 1.0)
+    }
+}